@@ -0,0 +1,188 @@
+//! Support for `--apply-generic-args`, which rewrites a target's `test_fuzz` attribute to use the
+//! suggested `generic_args` and `impl_generic_args`.
+
+use crate::{Executable, TestFuzz, changed::location};
+use anyhow::{Context, Result};
+use proc_macro2::LineColumn;
+use std::{
+    fs::{read_to_string, write},
+    iter,
+};
+use syn::{
+    Attribute, Expr, ImplItem, ItemFn, ItemImpl, Lit, MacroDelimiter, Meta, Token, Type,
+    punctuated::Punctuated,
+    spanned::Spanned,
+    visit::{self, Visit},
+};
+
+/// The `test_fuzz` arguments replaced by the suggested ones
+const REPLACED: [&str; 3] = ["generic_args", "impl_generic_args", "only_generic_args"];
+
+/// Rewrites the target's `test_fuzz` attribute so that its `generic_args`-related arguments are
+/// `args`, e.g., `generic_args = "u8"`. Returns false if the attribute could not be found or
+/// rewritten.
+pub fn apply_generic_args(
+    opts: &TestFuzz,
+    executable: &Executable,
+    target: &str,
+    args: &[String],
+) -> Result<bool> {
+    let Some((file, line)) = location(opts, executable, target)? else {
+        return Ok(false);
+    };
+    let file = opts.metadata().workspace_root.as_std_path().join(file);
+    let contents = read_to_string(&file)
+        .with_context(|| format!("`read_to_string` failed for `{}`", file.to_string_lossy()))?;
+    let syntax = syn::parse_file(&contents)
+        .with_context(|| format!("Could not parse `{}`", file.to_string_lossy()))?;
+    let mut finder = AttributeFinder {
+        target,
+        line,
+        attr: None,
+    };
+    finder.visit_file(&syntax);
+    let Some(rewritten) = finder.attr.and_then(|attr| rewrite(&contents, attr, args)) else {
+        return Ok(false);
+    };
+    write(&file, rewritten)
+        .with_context(|| format!("`write` failed for `{}`", file.to_string_lossy()))?;
+    Ok(true)
+}
+
+/// Finds the `test_fuzz` attribute of `target`, whose location was reported on `line`. For a
+/// method in a `test_fuzz_impl` block, the reported line is that of the `test_fuzz_impl`
+/// attribute.
+struct AttributeFinder<'ast, 'a> {
+    target: &'a str,
+    line: usize,
+    attr: Option<&'ast Attribute>,
+}
+
+impl<'ast> Visit<'ast> for AttributeFinder<'ast, '_> {
+    fn visit_item_fn(&mut self, item: &'ast ItemFn) {
+        if let Some(attr) = find_attribute(&item.attrs, "test_fuzz")
+            && attr.span().start().line == self.line
+            && target_name(attr, None, &item.sig.ident.to_string()) == self.target
+        {
+            self.attr = Some(attr);
+        }
+        visit::visit_item_fn(self, item);
+    }
+
+    fn visit_item_impl(&mut self, item: &'ast ItemImpl) {
+        if let Some(impl_attr) = find_attribute(&item.attrs, "test_fuzz_impl")
+            && impl_attr.span().start().line == self.line
+        {
+            let self_ty_base = match &*item.self_ty {
+                Type::Path(type_path) => type_path
+                    .path
+                    .segments
+                    .last()
+                    .map(|segment| segment.ident.to_string()),
+                _ => None,
+            };
+            for impl_item in &item.items {
+                if let ImplItem::Fn(impl_item_fn) = impl_item
+                    && let Some(attr) = find_attribute(&impl_item_fn.attrs, "test_fuzz")
+                    && target_name(
+                        attr,
+                        self_ty_base.as_deref(),
+                        &impl_item_fn.sig.ident.to_string(),
+                    ) == self.target
+                {
+                    self.attr = Some(attr);
+                }
+            }
+        }
+        visit::visit_item_impl(self, item);
+    }
+}
+
+fn find_attribute<'ast>(attrs: &'ast [Attribute], name: &str) -> Option<&'ast Attribute> {
+    attrs.iter().find(|attr| {
+        attr.path()
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == name)
+    })
+}
+
+/// Returns the name of the target that `attr` generates, mirroring the macro's naming.
+fn target_name(attr: &Attribute, self_ty_base: Option<&str>, ident: &str) -> String {
+    let rename = attribute_args(attr).and_then(|metas| {
+        metas.into_iter().find_map(|meta| match meta {
+            Meta::NameValue(name_value) if name_value.path.is_ident("rename") => {
+                match name_value.value {
+                    Expr::Lit(expr_lit) => match expr_lit.lit {
+                        Lit::Str(lit_str) => Some(lit_str.value()),
+                        _ => None,
+                    },
+                    _ => None,
+                }
+            }
+            _ => None,
+        })
+    });
+    rename.unwrap_or_else(|| {
+        self_ty_base.map_or_else(|| ident.to_owned(), |base| format!("{base}_{ident}"))
+    })
+}
+
+fn attribute_args(attr: &Attribute) -> Option<Punctuated<Meta, Token![,]>> {
+    match &attr.meta {
+        Meta::Path(_) => Some(Punctuated::new()),
+        Meta::List(list) => list.parse_args_with(Punctuated::parse_terminated).ok(),
+        Meta::NameValue(_) => None,
+    }
+}
+
+/// Returns `contents` with `attr`'s `generic_args`-related arguments replaced by `args`. The other
+/// arguments are copied from `contents` verbatim.
+fn rewrite(contents: &str, attr: &Attribute, args: &[String]) -> Option<String> {
+    let kept = attribute_args(attr)?
+        .iter()
+        .filter(|meta| !REPLACED.iter().any(|name| meta.path().is_ident(name)))
+        .map(|meta| {
+            let start = offset(contents, meta.span().start())?;
+            let end = offset(contents, meta.span().end())?;
+            contents.get(start..end).map(ToOwned::to_owned)
+        })
+        .collect::<Option<Vec<_>>>()?;
+    let args = kept
+        .into_iter()
+        .chain(args.iter().cloned())
+        .collect::<Vec<_>>();
+    let (start, end, replacement) = match &attr.meta {
+        Meta::Path(path) => {
+            let end = offset(contents, path.span().end())?;
+            (end, end, format!("({})", args.join(", ")))
+        }
+        Meta::List(list) => {
+            let MacroDelimiter::Paren(paren) = &list.delimiter else {
+                return None;
+            };
+            (
+                offset(contents, paren.span.open().end())?,
+                offset(contents, paren.span.close().start())?,
+                args.join(", "),
+            )
+        }
+        Meta::NameValue(_) => return None,
+    };
+    Some(contents[..start].to_owned() + &replacement + &contents[end..])
+}
+
+/// Converts a line (one-based) and column (zero-based, in characters) to a byte offset.
+fn offset(contents: &str, LineColumn { line, column }: LineColumn) -> Option<usize> {
+    let line_start = contents
+        .split_inclusive('\n')
+        .take(line.checked_sub(1)?)
+        .map(str::len)
+        .sum::<usize>();
+    let rest = contents.get(line_start..)?;
+    rest.char_indices()
+        .map(|(i, _)| i)
+        .chain(iter::once(rest.len()))
+        .nth(column)
+        .map(|i| line_start + i)
+}
@@ -0,0 +1,184 @@
+use crate::{DEFAULT_TIMEOUT, Executable, Job, TestFuzz, to_exec::ToExec};
+use anyhow::{Context, Result, anyhow};
+use log::debug;
+use runtime::{REPLAY_FILE_PREFIX, REPLAY_RESULT_PREFIX};
+use std::{
+    collections::BTreeMap,
+    io::{ErrorKind, sink},
+    path::Path,
+    thread,
+    time::Duration,
+};
+use subprocess::{ExitStatus, Redirection, unix::ExitStatusExt};
+
+/// A file's output on stderr, the exit status it would have caused had it been run in its own
+/// process, and the time it took to run
+pub type BatchResult = (Vec<u8>, ExitStatus, Duration);
+
+/// libtest's exit code when a test fails
+const TEST_FAILED: i32 = 101;
+
+/// Runs the target on `jobs` using one process per directory, in the runtime's
+/// `TEST_FUZZ_REPLAY_DIR` mode.
+///
+/// A job has no result if the process timed out or died while running it, e.g., because of a stack
+/// overflow, or if the process never reached it. A job also has no result if the target was built
+/// with a `test-fuzz` that predates `TEST_FUZZ_REPLAY_DIR`. Jobs without results should be run in
+/// their own processes.
+pub fn run_batches(
+    opts: &TestFuzz,
+    executable: &Executable,
+    envs: &[(&str, &str)],
+    args: &[String],
+    jobs: &[Job],
+) -> Result<Vec<Option<BatchResult>>> {
+    let mut dirs = BTreeMap::<&Path, Vec<usize>>::new();
+    for (i, job) in jobs.iter().enumerate() {
+        if let Some(dir) = job.path.parent() {
+            dirs.entry(dir).or_default().push(i);
+        }
+    }
+
+    let mut results = jobs.iter().map(|_| None).collect::<Vec<_>>();
+    thread::scope(|scope| -> Result<()> {
+        let handles = dirs
+            .keys()
+            .map(|dir| scope.spawn(|| run_batch(opts, executable, envs, args, dir)))
+            .collect::<Vec<_>>();
+        for ((_, indices), handle) in dirs.iter().zip(handles) {
+            #[allow(clippy::panic)]
+            let mut batch_results = handle
+                .join()
+                .unwrap_or_else(|_| panic!("Batch thread panicked"))?;
+            for &i in indices {
+                let file_name = jobs[i].path.file_name().unwrap_or_default();
+                results[i] = batch_results.remove(&*file_name.to_string_lossy());
+            }
+        }
+        Ok(())
+    })?;
+
+    Ok(results)
+}
+
+fn run_batch(
+    opts: &TestFuzz,
+    executable: &Executable,
+    envs: &[(&str, &str)],
+    args: &[String],
+    dir: &Path,
+) -> Result<BTreeMap<String, BatchResult>> {
+    let exec = opts
+        .command([&executable.path])
+        .to_exec()
+        .env_extend(envs.iter().copied())
+        .env("TEST_FUZZ_REPLAY_DIR", dir)
+        .args(args.iter().map(String::as_str))
+        .stdin(Redirection::Null)
+        .stdout(Redirection::Null)
+        .stderr(Redirection::Pipe);
+    debug!("{exec:?}");
+    let exec_str = format!("{exec:?}");
+    let mut job = exec
+        .start()
+        .with_context(|| format!("`start` failed for `{exec_str}`"))?;
+    let time = Duration::from_secs(opts.timeout.unwrap_or(DEFAULT_TIMEOUT));
+    let mut communicator = job
+        .communicate()
+        .with_context(|| format!("`communicate` failed for `{job:?}`"))?
+        .limit_time(time);
+
+    // smoelius: Keep reading as long as at least one file finishes per timeout period. Otherwise,
+    // assume the current file hangs, and kill the process. The current file and any that follow
+    // it are then left to be run in their own processes.
+    let mut stderr = Vec::new();
+    let mut n_results = 0;
+    loop {
+        match communicator.read_to(sink(), &mut stderr) {
+            Ok(()) => break,
+            Err(error) if error.kind() == ErrorKind::TimedOut => {
+                let n_results_prev = n_results;
+                n_results = String::from_utf8_lossy(&stderr)
+                    .lines()
+                    .filter(|line| line.starts_with(REPLAY_RESULT_PREFIX))
+                    .count();
+                if n_results == n_results_prev {
+                    debug!("Killing `{exec_str}`: no file finished within {time:?}");
+                    job.kill()
+                        .with_context(|| format!("`kill` failed for `{job:?}`"))?;
+                    break;
+                }
+            }
+            Err(error) => {
+                job.kill()
+                    .with_context(|| format!("`kill` failed for `{job:?}`"))?;
+                return Err(anyhow!(error));
+            }
+        }
+    }
+    let _ = job
+        .wait()
+        .with_context(|| format!("`wait` failed for `{job:?}`"))?;
+
+    Ok(parse(&String::from_utf8_lossy(&stderr)))
+}
+
+/// Parses the output of a `TEST_FUZZ_REPLAY_DIR` process into results keyed by file name. Files
+/// without a result line are omitted.
+fn parse(stderr: &str) -> BTreeMap<String, BatchResult> {
+    let mut results = BTreeMap::new();
+    let mut current: Option<(&str, String)> = None;
+    for line in stderr.lines() {
+        if let Some(file_name) = line.strip_prefix(REPLAY_FILE_PREFIX) {
+            current = Some((file_name, String::new()));
+        } else if let Some(result) = line.strip_prefix(REPLAY_RESULT_PREFIX) {
+            let Some((file_name, output)) = current.take() else {
+                continue;
+            };
+            let Some((outcome, micros)) = result.split_once(' ') else {
+                continue;
+            };
+            let status = ExitStatus::from_raw(match outcome {
+                "ok" => 0,
+                "panicked" => TEST_FAILED << 8,
+                _ => continue,
+            });
+            let elapsed = Duration::from_micros(micros.parse().unwrap_or_default());
+            results.insert(file_name.to_owned(), (output.into_bytes(), status, elapsed));
+        } else if let Some((_, output)) = &mut current {
+            output.push_str(line);
+            output.push('\n');
+        }
+    }
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse;
+    use std::time::Duration;
+
+    #[test]
+    fn results() {
+        let results = parse(
+            "\
+test-fuzz replay file: a
+Args { x: false }
+test-fuzz replay result: ok 12
+test-fuzz replay file: b
+thread 'target_fuzz__::entry' panicked at tests/assert.rs:3:5:
+assertion failed: !x
+test-fuzz replay result: panicked 34
+test-fuzz replay file: c
+",
+        );
+        assert_eq!(2, results.len());
+        let (output, status, elapsed) = &results["a"];
+        assert_eq!(b"Args { x: false }\n", output.as_slice());
+        assert!(status.success());
+        assert_eq!(Duration::from_micros(12), *elapsed);
+        let (output, status, _) = &results["b"];
+        assert!(String::from_utf8_lossy(output).ends_with("assertion failed: !x\n"));
+        assert_eq!(Some(101), status.code());
+    }
+}
@@ -24,8 +24,44 @@ Try `cargo afl fuzz --help` to see additional fuzzer options.
 ")]
 #[remain::sorted]
 struct TestFuzzWithDeprecations {
+    #[arg(
+        long,
+        help = "With --suggest-generic-args, rewrite each target's `test_fuzz` attribute to use \
+                the suggested values"
+    )]
+    apply_generic_args: bool,
     #[arg(long, help = "Display backtraces")]
     backtrace: bool,
+    #[arg(
+        long,
+        help = "With --replay, --display, or --coverage, run all of a directory's files in one \
+                process; files that panic are rerun in their own processes"
+    )]
+    batch: bool,
+    #[arg(
+        long,
+        value_name = "REV",
+        help = "Fuzz only the targets affected by the changes since git revision <REV>, i.e., \
+                targets whose functions, or functions they call within their package, changed"
+    )]
+    changed_since: Option<String>,
+    #[arg(
+        long,
+        value_name = "OBJECT",
+        hide_possible_values = true,
+        help = "Replay corpus and compare each call's outcome to the one recorded when the corpus \
+                file was generated; fail if any differ. Only targets that use `capture_ret` \
+                record outcomes. To check with instrumentation, use corpus-instrumented."
+    )]
+    check_regressions: Option<Object>,
+    #[arg(
+        long,
+        help = "Auto-generate missing corpora, fuzz for --max-total-time <SECONDS> (default 60; \
+                per target if there are enough cpus, otherwise for all targets combined), replay \
+                any crashes, and write a JUnit report to test-fuzz.xml; fail if new crashes were \
+                found. Implies --no-ui."
+    )]
+    ci: bool,
     #[arg(
         long,
         help = "Move one target's crashes, hangs, and work queue to its corpus; to consolidate \
@@ -45,7 +81,8 @@ struct TestFuzzWithDeprecations {
     #[arg(
         long,
         value_name = "N",
-        help = "Fuzz using at most <N> cpus; default is all but one"
+        help = "Use at most <N> cpus when fuzzing, replaying, displaying, or generating coverage; \
+                default is all but one"
     )]
     cpus: Option<usize>,
     #[arg(
@@ -73,6 +110,21 @@ struct TestFuzzWithDeprecations {
         help = "Space or comma separated list of features to activate"
     )]
     features: Vec<String>,
+    #[arg(
+        long,
+        value_name = "N",
+        help = "Fuzz each target with <N> instances (one main and <N>-1 secondary) sharing the \
+                target's output directory"
+    )]
+    jobs: Option<usize>,
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "With --replay or --check-regressions, write a JUnit report to <PATH>, with one \
+                testsuite per target and one testcase per file. With --ci, write the report to \
+                <PATH> instead of test-fuzz.xml."
+    )]
+    junit: Option<String>,
     #[arg(long, help = "List fuzz targets")]
     list: bool,
     #[arg(long, value_name = "PATH", help = "Path to Cargo.toml")]
@@ -83,6 +135,13 @@ struct TestFuzzWithDeprecations {
         help = "Fuzz at most <SECONDS> of time (equivalent to -- -V <SECONDS>)"
     )]
     max_total_time: Option<u64>,
+    #[arg(
+        long,
+        value_name = "ADDR",
+        help = "While fuzzing, serve each target's AFL++ statistics in Prometheus text format \
+                over HTTP at <ADDR>, e.g., 127.0.0.1:9100"
+    )]
+    metrics_addr: Option<String>,
     #[arg(long, help = "Do not activate the `default` feature")]
     no_default_features: bool,
     #[arg(long, hide = true)]
@@ -91,6 +150,20 @@ struct TestFuzzWithDeprecations {
     no_run: bool,
     #[arg(long, help = "Disable user interface")]
     no_ui: bool,
+    #[arg(
+        long,
+        value_name = "COMMAND",
+        help = "While fuzzing, run <COMMAND> with `sh -c` for each new crash. The command's \
+                environment describes the crash; see README.md."
+    )]
+    on_crash: Option<String>,
+    #[arg(
+        long,
+        value_name = "COMMAND",
+        help = "While fuzzing, run <COMMAND> with `sh -c` for each new hang. The command's \
+                environment describes the hang; see README.md."
+    )]
+    on_hang: Option<String>,
     #[arg(short, long, help = "Package containing fuzz target")]
     package: Option<String>,
     #[arg(long, help = "Enable persistent mode fuzzing")]
@@ -122,6 +195,14 @@ struct TestFuzzWithDeprecations {
     reset_all: bool,
     #[arg(long, help = "Resume target's last fuzzing session")]
     resume: bool,
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        help = "If there are not sufficiently many cpus to fuzz all targets simultaneously, stop \
+                fuzzing a target once it has gone <SECONDS> of fuzzing without a new find; by \
+                default, targets are never retired"
+    )]
+    retire_after: Option<u64>,
     #[arg(long, help = "Stop fuzzing once a crash is found")]
     run_until_crash: bool,
     #[arg(
@@ -132,6 +213,25 @@ struct TestFuzzWithDeprecations {
                 them in intervals of <SECONDS>"
     )]
     slice: u64,
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "While fuzzing, append a JSON snapshot of each target's AFL++ statistics to <PATH> \
+                once a minute"
+    )]
+    stats_file: Option<String>,
+    #[arg(
+        long,
+        help = "Print a table summarizing each target's fuzzing progress (execs/sec, corpus size, \
+                coverage, crashes, hangs, and last new find), then exit"
+    )]
+    status: bool,
+    #[arg(
+        long,
+        help = "Suggest `generic_args` and `impl_generic_args` for generic targets, based on the \
+                instantiations recorded when running tests"
+    )]
+    suggest_generic_args: bool,
     #[arg(
         long,
         value_name = "NAME",
@@ -161,7 +261,12 @@ struct TestFuzzWithDeprecations {
 impl From<TestFuzzWithDeprecations> for super::TestFuzz {
     fn from(opts: TestFuzzWithDeprecations) -> Self {
         let TestFuzzWithDeprecations {
+            apply_generic_args,
             backtrace,
+            batch,
+            changed_since,
+            check_regressions,
+            ci,
             consolidate,
             consolidate_all,
             coverage,
@@ -170,13 +275,18 @@ impl From<TestFuzzWithDeprecations> for super::TestFuzz {
             exact,
             exit_code,
             features,
+            jobs,
+            junit,
             list,
             manifest_path,
             max_total_time,
+            metrics_addr,
             no_default_features,
             no_instrumentation,
             no_run,
             no_ui,
+            on_crash,
+            on_hang,
             package,
             persistent,
             pretty,
@@ -185,8 +295,12 @@ impl From<TestFuzzWithDeprecations> for super::TestFuzz {
             reset,
             reset_all,
             resume,
+            retire_after,
             run_until_crash,
             slice,
+            stats_file,
+            status,
+            suggest_generic_args,
             test,
             timeout,
             verbose,
@@ -200,7 +314,12 @@ impl From<TestFuzzWithDeprecations> for super::TestFuzz {
             );
         }
         Self {
+            apply_generic_args,
             backtrace,
+            batch,
+            changed_since,
+            check_regressions,
+            ci,
             consolidate,
             consolidate_all,
             coverage,
@@ -209,12 +328,17 @@ impl From<TestFuzzWithDeprecations> for super::TestFuzz {
             exact,
             exit_code,
             features,
+            jobs,
+            junit,
             list,
             manifest_path,
             max_total_time,
+            metrics_addr,
             no_default_features,
             no_run,
             no_ui,
+            on_crash,
+            on_hang,
             package,
             persistent,
             pretty,
@@ -223,8 +347,12 @@ impl From<TestFuzzWithDeprecations> for super::TestFuzz {
             reset,
             reset_all,
             resume,
+            retire_after,
             run_until_crash,
             slice,
+            stats_file,
+            status,
+            suggest_generic_args,
             test,
             timeout,
             verbose,
@@ -0,0 +1,509 @@
+//! Support for `--changed-since`, which restricts the fuzz targets to those affected by the changes
+//! since a git revision.
+//!
+//! A target is affected if a changed line falls within its function, or within a function that
+//! its function transitively calls. Calls are resolved by name only, and only to functions within
+//! the target's package. So a target may be selected unnecessarily, e.g., if a function of the
+//! same name changed in another module.
+
+use crate::{ENTRY_SUFFIX, Executable, TestFuzz};
+use anyhow::{Context, Result, ensure};
+use log::debug;
+use runtime::LOCATION_PREFIX;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs::{read_dir, read_to_string},
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+};
+use syn::{
+    Expr, ExprCall, ExprMethodCall, ImplItemFn, ItemFn, ItemImpl, Macro, Token, TraitItemFn,
+    punctuated::Punctuated,
+    spanned::Spanned,
+    visit::{self, Visit},
+};
+
+pub fn filter_changed_targets(
+    opts: &TestFuzz,
+    rev: &str,
+    executable_targets: &[(Executable, Vec<String>)],
+) -> Result<Vec<(Executable, Vec<String>)>> {
+    let workspace_root = opts.metadata().workspace_root.as_std_path();
+    let changed_lines = ChangedLines::since(opts, workspace_root, rev)?;
+    let mut packages = BTreeMap::<PathBuf, Vec<Unit>>::new();
+
+    let mut filtered = Vec::new();
+    for (executable, targets) in executable_targets {
+        let mut changed_targets = Vec::new();
+        for target in targets {
+            let Some((file, line)) = location(opts, executable, target)? else {
+                eprintln!(
+                    "Warning: Could not determine the location of target `{target}`; treating it \
+                     as changed. Is `test-fuzz` up to date?"
+                );
+                changed_targets.push(target.clone());
+                continue;
+            };
+            let file = workspace_root.join(file);
+            let Some(package_root) = package_root(&file) else {
+                changed_targets.push(target.clone());
+                continue;
+            };
+            if !packages.contains_key(&package_root) {
+                let units = package_units(&package_root)?;
+                packages.insert(package_root.clone(), units);
+            }
+            let units = &packages[&package_root];
+            if target_changed(&changed_lines, units, &file, line) {
+                changed_targets.push(target.clone());
+            } else {
+                debug!("Target `{target}` is unaffected by the changes since `{rev}`");
+            }
+        }
+        if !changed_targets.is_empty() {
+            filtered.push((executable.clone(), changed_targets));
+        }
+    }
+
+    Ok(filtered)
+}
+
+/// Asks the target's `entry` test for the location of the target's `test_fuzz` attribute.
+pub fn location(
+    opts: &TestFuzz,
+    executable: &Executable,
+    target: &str,
+) -> Result<Option<(PathBuf, usize)>> {
+    let mut command = opts.command([&executable.path]);
+    command.env("TEST_FUZZ_LOCATION", "1").args([
+        "--exact",
+        &(target.to_owned() + ENTRY_SUFFIX),
+        "--nocapture",
+    ]);
+    debug!("{command:?}");
+    let output = command
+        .output()
+        .with_context(|| format!("Could not get output of `{command:?}`"))?;
+    ensure!(output.status.success(), "Command failed: {command:?}");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    // smoelius: The location need not start a line. The test harness prints `test ... ` without a
+    // trailing newline before running the test.
+    Ok(stdout.lines().find_map(|line| {
+        let (_, location) = line.split_once(LOCATION_PREFIX)?;
+        let (file, line) = location.rsplit_once(':')?;
+        Some((PathBuf::from(file), line.parse().ok()?))
+    }))
+}
+
+/// Lines changed since a git revision
+#[derive(Debug, Default)]
+struct ChangedLines {
+    /// Changed lines keyed by absolute file path
+    files: BTreeMap<PathBuf, Vec<RangeInclusive<usize>>>,
+    /// Whether some changed file's path could not be parsed, in which case any line could have
+    /// changed
+    unknown: bool,
+}
+
+impl ChangedLines {
+    /// Compares the working tree to the merge base of `rev` and `HEAD`, so that changes made on
+    /// `rev`'s branch after the current branch diverged from it are not counted.
+    fn since(opts: &TestFuzz, dir: &Path, rev: &str) -> Result<Self> {
+        let toplevel = git(opts, dir, &["rev-parse", "--show-toplevel"])?;
+        let merge_base = git(opts, dir, &["merge-base", rev, "HEAD"])?;
+        let diff = git(
+            opts,
+            dir,
+            &[
+                "diff",
+                "--unified=0",
+                "--no-color",
+                "--no-ext-diff",
+                merge_base.trim(),
+                "--",
+            ],
+        )?;
+        Ok(Self::parse(Path::new(toplevel.trim()), &diff))
+    }
+
+    fn parse(toplevel: &Path, diff: &str) -> Self {
+        let mut changed_lines = Self::default();
+        let mut file = None;
+        for line in diff.lines() {
+            if let Some(path) = line.strip_prefix("+++ ") {
+                // smoelius: A deleted file's path is `/dev/null`. Any target that called into it
+                // must itself have changed.
+                if path == "/dev/null" {
+                    file = None;
+                    continue;
+                }
+                file = unquote(path)
+                    .as_deref()
+                    .and_then(|path| path.strip_prefix("b/"))
+                    .map(|path| toplevel.join(path));
+                if file.is_none() {
+                    eprintln!(
+                        "Warning: Could not parse path `{path}` in `git diff` output; treating \
+                         all targets as changed"
+                    );
+                    changed_lines.unknown = true;
+                }
+                continue;
+            }
+            let (Some(file), Some(hunk)) = (&file, line.strip_prefix("@@ ")) else {
+                continue;
+            };
+            // smoelius: A hunk header has the form `@@ -a[,b] +c[,d] @@`. The new lines are `c`
+            // through `c + d - 1`. If `d` is zero, lines were only removed, and they were removed
+            // from between lines `c` and `c + 1`.
+            let Some(new) = hunk
+                .split_whitespace()
+                .find_map(|range| range.strip_prefix('+'))
+            else {
+                continue;
+            };
+            let (start, len) = new.split_once(',').unwrap_or((new, "1"));
+            let (Ok(start), Ok(len)) = (start.parse::<usize>(), len.parse::<usize>()) else {
+                continue;
+            };
+            let range = if len == 0 {
+                start..=start + 1
+            } else {
+                start..=start + len - 1
+            };
+            changed_lines
+                .files
+                .entry(file.clone())
+                .or_default()
+                .push(range);
+        }
+        changed_lines
+    }
+
+    fn intersects(&self, file: &Path, lines: &RangeInclusive<usize>) -> bool {
+        self.unknown
+            || self.files.get(file).is_some_and(|ranges| {
+                ranges
+                    .iter()
+                    .any(|range| range.start() <= lines.end() && lines.start() <= range.end())
+            })
+    }
+}
+
+/// Removes the quotes from a path that git quoted because it contains unusual characters, e.g.,
+/// `"b/sp\303\251cial.rs"`. Returns `None` if the path is malformed or is not valid UTF-8.
+fn unquote(path: &str) -> Option<String> {
+    let Some(quoted) = path.strip_prefix('"') else {
+        return Some(path.to_owned());
+    };
+    let quoted = quoted.strip_suffix('"')?;
+    let mut bytes = Vec::new();
+    let mut iter = quoted.bytes();
+    while let Some(byte) = iter.next() {
+        if byte != b'\\' {
+            bytes.push(byte);
+            continue;
+        }
+        let byte = match iter.next()? {
+            b'a' => 0x07,
+            b'b' => 0x08,
+            b't' => b'\t',
+            b'n' => b'\n',
+            b'v' => 0x0b,
+            b'f' => 0x0c,
+            b'r' => b'\r',
+            digit @ b'0'..=b'3' => {
+                let mut value = digit - b'0';
+                for _ in 0..2 {
+                    let digit = iter.next().filter(|digit| (b'0'..=b'7').contains(digit))?;
+                    value = value * 8 + (digit - b'0');
+                }
+                value
+            }
+            byte @ (b'"' | b'\\') => byte,
+            _ => return None,
+        };
+        bytes.push(byte);
+    }
+    String::from_utf8(bytes).ok()
+}
+
+fn git(opts: &TestFuzz, dir: &Path, args: &[&str]) -> Result<String> {
+    let mut command = opts.command(["git"]);
+    command.current_dir(dir).args(args);
+    debug!("{command:?}");
+    let output = command
+        .output()
+        .with_context(|| format!("Could not get output of `{command:?}`"))?;
+    ensure!(
+        output.status.success(),
+        "Command failed: {command:?}\nstderr: ```\n{}\n```",
+        String::from_utf8_lossy(&output.stderr).trim_end()
+    );
+    String::from_utf8(output.stdout).map_err(Into::into)
+}
+
+/// A function or `impl` block, and the names of the functions and methods it calls
+#[derive(Debug)]
+struct Unit {
+    name: Option<String>,
+    file: PathBuf,
+    lines: RangeInclusive<usize>,
+    callees: BTreeSet<String>,
+}
+
+/// Returns true if a changed line falls within the innermost unit containing `file:line`, or
+/// within any function that the unit transitively calls.
+fn target_changed(changed_lines: &ChangedLines, units: &[Unit], file: &Path, line: usize) -> bool {
+    let Some(target) = units
+        .iter()
+        .filter(|unit| unit.file == file && unit.lines.contains(&line))
+        .min_by_key(|unit| unit.lines.end() - unit.lines.start())
+    else {
+        // smoelius: If the target cannot be found, err on the side of fuzzing it.
+        return true;
+    };
+
+    let mut visited = BTreeSet::new();
+    let mut worklist = vec![target];
+    while let Some(unit) = worklist.pop() {
+        if changed_lines.intersects(&unit.file, &unit.lines) {
+            return true;
+        }
+        for callee in &unit.callees {
+            if !visited.insert(callee) {
+                continue;
+            }
+            worklist.extend(
+                units
+                    .iter()
+                    .filter(|unit| unit.name.as_ref() == Some(callee)),
+            );
+        }
+    }
+
+    false
+}
+
+/// Returns the directory of the nearest `Cargo.toml` above `file`.
+fn package_root(file: &Path) -> Option<PathBuf> {
+    file.ancestors()
+        .skip(1)
+        .find(|dir| dir.join("Cargo.toml").exists())
+        .map(Path::to_path_buf)
+}
+
+fn package_units(package_root: &Path) -> Result<Vec<Unit>> {
+    let mut units = Vec::new();
+    for file in rust_files(package_root)? {
+        let contents = read_to_string(&file)
+            .with_context(|| format!("`read_to_string` failed for `{}`", file.to_string_lossy()))?;
+        // smoelius: Files that do not parse cannot contain compiled targets or their callees.
+        let Ok(syntax) = syn::parse_file(&contents) else {
+            debug!("Could not parse `{}`", file.to_string_lossy());
+            continue;
+        };
+        let mut collector = UnitCollector {
+            file: &file,
+            units: &mut units,
+        };
+        collector.visit_file(&syntax);
+    }
+    Ok(units)
+}
+
+/// Returns the package's Rust source files, skipping `target` directories and hidden directories.
+fn rust_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in read_dir(dir)
+        .with_context(|| format!("`read_dir` failed for `{}`", dir.to_string_lossy()))?
+    {
+        let entry =
+            entry.with_context(|| format!("`read_dir` failed for `{}`", dir.to_string_lossy()))?;
+        let path = entry.path();
+        let file_name = entry.file_name().to_string_lossy().into_owned();
+        if path.is_dir() {
+            if file_name != "target" && !file_name.starts_with('.') {
+                files.extend(rust_files(&path)?);
+            }
+        } else if path.extension().is_some_and(|extension| extension == "rs") {
+            files.push(path);
+        }
+    }
+    Ok(files)
+}
+
+struct UnitCollector<'a> {
+    file: &'a Path,
+    units: &'a mut Vec<Unit>,
+}
+
+impl UnitCollector<'_> {
+    fn push(&mut self, name: Option<String>, span: proc_macro2::Span, callees: CalleeCollector) {
+        self.units.push(Unit {
+            name,
+            file: self.file.to_path_buf(),
+            lines: span.start().line..=span.end().line,
+            callees: callees.0,
+        });
+    }
+}
+
+impl<'ast> Visit<'ast> for UnitCollector<'_> {
+    fn visit_item_fn(&mut self, item: &'ast ItemFn) {
+        let mut callees = CalleeCollector::default();
+        callees.visit_block(&item.block);
+        self.push(Some(item.sig.ident.to_string()), item.span(), callees);
+        visit::visit_item_fn(self, item);
+    }
+
+    fn visit_impl_item_fn(&mut self, item: &'ast ImplItemFn) {
+        let mut callees = CalleeCollector::default();
+        callees.visit_block(&item.block);
+        self.push(Some(item.sig.ident.to_string()), item.span(), callees);
+        visit::visit_impl_item_fn(self, item);
+    }
+
+    fn visit_trait_item_fn(&mut self, item: &'ast TraitItemFn) {
+        let mut callees = CalleeCollector::default();
+        if let Some(block) = &item.default {
+            callees.visit_block(block);
+        }
+        self.push(Some(item.sig.ident.to_string()), item.span(), callees);
+        visit::visit_trait_item_fn(self, item);
+    }
+
+    // smoelius: A `test_fuzz_impl` attribute's location is that of its `impl` block. The block
+    // calls whatever its methods call.
+    fn visit_item_impl(&mut self, item: &'ast ItemImpl) {
+        let mut callees = CalleeCollector::default();
+        callees.visit_item_impl(item);
+        self.push(None, item.span(), callees);
+        visit::visit_item_impl(self, item);
+    }
+}
+
+#[derive(Default)]
+struct CalleeCollector(BTreeSet<String>);
+
+impl<'ast> Visit<'ast> for CalleeCollector {
+    fn visit_expr_call(&mut self, expr: &'ast ExprCall) {
+        if let Expr::Path(path) = &*expr.func
+            && let Some(segment) = path.path.segments.last()
+        {
+            self.0.insert(segment.ident.to_string());
+        }
+        visit::visit_expr_call(self, expr);
+    }
+
+    fn visit_expr_method_call(&mut self, expr: &'ast ExprMethodCall) {
+        self.0.insert(expr.method.to_string());
+        visit::visit_expr_method_call(self, expr);
+    }
+
+    // smoelius: Macro arguments are opaque to `syn`. Make a best effort to find calls within
+    // them by parsing them as comma-separated expressions, as in `assert!(f(x))`.
+    fn visit_macro(&mut self, mac: &'ast Macro) {
+        if let Ok(exprs) = mac.parse_body_with(Punctuated::<Expr, Token![,]>::parse_terminated) {
+            for expr in &exprs {
+                self.visit_expr(expr);
+            }
+        }
+        visit::visit_macro(self, mac);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    use super::{ChangedLines, UnitCollector, target_changed, unquote};
+    use std::path::Path;
+    use syn::visit::Visit;
+
+    const DIFF: &str = "\
+diff --git a/src/lib.rs b/src/lib.rs
+index 0000000..1111111 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -12 +12 @@ fn leaf() -> u8 {
+-    1
++    2
+@@ -30,2 +29,0 @@ fn other() {
+";
+
+    const SOURCE: &str = "\
+#[test_fuzz::test_fuzz]
+fn calls_leaf(x: u8) {
+    middle(x);
+}
+
+fn middle(x: u8) {
+    assert!(x != leaf());
+}
+
+fn leaf() -> u8 {
+    // changed below
+    2
+}
+
+#[test_fuzz::test_fuzz]
+fn unaffected(x: u8) {
+    let _ = x.checked_add(1);
+}
+
+fn other() {
+    let _ = 0;
+}
+";
+
+    #[test]
+    fn parse_diff() {
+        let changed_lines = ChangedLines::parse(Path::new("/repo"), DIFF);
+        let file = Path::new("/repo/src/lib.rs");
+        assert!(changed_lines.intersects(file, &(12..=12)));
+        assert!(changed_lines.intersects(file, &(29..=30)));
+        assert!(!changed_lines.intersects(file, &(13..=28)));
+        assert!(!changed_lines.intersects(Path::new("/repo/src/main.rs"), &(12..=12)));
+    }
+
+    #[test]
+    fn parse_quoted_paths() {
+        let diff = DIFF.replace(
+            "+++ b/src/lib.rs",
+            r#"+++ "b/src/sp\303\251cial \"lib\".rs""#,
+        );
+        let changed_lines = ChangedLines::parse(Path::new("/repo"), &diff);
+        let file = Path::new("/repo/src/sp\u{e9}cial \"lib\".rs");
+        assert!(changed_lines.intersects(file, &(12..=12)));
+        assert!(!changed_lines.intersects(file, &(13..=28)));
+
+        // smoelius: If a path cannot be parsed, any line could have changed.
+        let diff = DIFF.replace("+++ b/src/lib.rs", r#"+++ "b/src/lib.rs"#);
+        let changed_lines = ChangedLines::parse(Path::new("/repo"), &diff);
+        assert!(changed_lines.intersects(Path::new("/repo/src/main.rs"), &(13..=28)));
+    }
+
+    #[test]
+    fn unquoted_paths() {
+        assert_eq!(Some("b/src/lib.rs"), unquote("b/src/lib.rs").as_deref());
+        assert_eq!(Some("b/a\tb\\c"), unquote(r#""b/a\tb\\c""#).as_deref());
+        assert_eq!(None, unquote(r#""b/a\9""#));
+        assert_eq!(None, unquote(r#""b/\377""#));
+    }
+
+    #[test]
+    fn transitive_callees() {
+        let file = Path::new("/repo/src/lib.rs");
+        let changed_lines = ChangedLines::parse(Path::new("/repo"), DIFF);
+        let mut units = Vec::new();
+        UnitCollector {
+            file,
+            units: &mut units,
+        }
+        .visit_file(&syn::parse_file(SOURCE).unwrap());
+        // smoelius: `calls_leaf` calls `middle`, which calls `leaf` from within `assert!`.
+        assert!(target_changed(&changed_lines, &units, file, 1));
+        assert!(!target_changed(&changed_lines, &units, file, 16));
+    }
+}
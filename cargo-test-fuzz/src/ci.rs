@@ -0,0 +1,205 @@
+use crate::{
+    Executable, Object, Outcome, TestFuzz, auto_generate_corpus, flags_and_dirs, for_each_entry,
+    fuzz,
+    hooks::extract_panic_message,
+    junit::{TestCase, TestResult, TestSuite, write_report},
+};
+use anyhow::{Result, ensure};
+use internal::dirs::{corpus_directory_from_target, crashes_directory_from_target};
+use std::{
+    collections::BTreeSet,
+    fmt::Write,
+    fs::read_dir,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+/// Fuzzing time per target in `--ci` mode, unless `--max-total-time` is given
+pub const CI_MAX_TOTAL_TIME: u64 = 60;
+
+/// Path of the `JUnit` XML report written in `--ci` mode, unless `--junit` is given
+pub const CI_REPORT: &str = "test-fuzz.xml";
+
+/// Runs a bounded fuzzing campaign, replays the crashes it found, and writes a `JUnit` XML report
+/// with one testcase per target.
+///
+/// Fails if any target produced a new crash, or could not be fuzzed. Crashes that existed before
+/// fuzzing started are replayed and mentioned in the report, but do not cause a failure.
+#[allow(clippy::too_many_lines)]
+pub fn ci(opts: &TestFuzz, executable_targets: &[(Executable, String)]) -> Result<()> {
+    let known_crashes = executable_targets
+        .iter()
+        .map(|(executable, target)| crash_files(&executable.name, target))
+        .collect::<Vec<_>>();
+
+    // smoelius: `fuzz` auto-generates missing corpora too, but fails if any cannot be generated. A
+    // target whose corpus cannot be generated is instead reported as an error.
+    let errors = executable_targets
+        .iter()
+        .map(|(executable, target)| {
+            let corpus_dir = corpus_directory_from_target(&executable.name, target);
+            if corpus_dir.exists() {
+                return None;
+            }
+            eprintln!(
+                "Could not find `{}`. Trying to auto-generate it...",
+                corpus_dir.to_string_lossy(),
+            );
+            if let Err(error) = auto_generate_corpus(opts, executable, target) {
+                return Some(format!("{error:#}"));
+            }
+            if !corpus_dir.exists() {
+                return Some(format!(
+                    "Could not find or auto-generate `{}`. Please ensure `{target}` is tested.",
+                    corpus_dir.to_string_lossy(),
+                ));
+            }
+            eprintln!("Auto-generated `{}`.", corpus_dir.to_string_lossy());
+            None
+        })
+        .collect::<Vec<_>>();
+
+    let fuzzable = executable_targets
+        .iter()
+        .zip(&errors)
+        .filter(|(_, error)| error.is_none())
+        .map(|(executable_target, _)| executable_target.clone())
+        .collect::<Vec<_>>();
+
+    let started = Instant::now();
+    let fuzz_error = if fuzzable.is_empty() {
+        None
+    } else {
+        fuzz(opts, &fuzzable)
+            .err()
+            .map(|error| format!("Fuzzing failed: {error:#}"))
+    };
+    let elapsed = started.elapsed();
+
+    let replay_opts = TestFuzz {
+        replay: Some(Object::Crashes),
+        ..opts.clone()
+    };
+
+    let mut testcases = Vec::new();
+    let mut n_crashing = 0;
+    for (((executable, target), error), known_crashes) in
+        executable_targets.iter().zip(errors).zip(known_crashes)
+    {
+        let mut testcase = TestCase {
+            name: target.clone(),
+            classname: executable.name.clone(),
+            time: elapsed,
+            result: TestResult::Passed,
+            system_out: None,
+            system_err: None,
+        };
+
+        if let Some(error) = error.or_else(|| fuzz_error.clone()) {
+            testcase.time = Duration::ZERO;
+            testcase.result = TestResult::Error {
+                message: error.lines().next().unwrap_or_default().to_owned(),
+                text: error,
+            };
+            testcases.push(testcase);
+            continue;
+        }
+
+        let crashes = crash_files(&executable.name, target);
+        if crashes.is_empty() {
+            testcases.push(testcase);
+            continue;
+        }
+
+        println!("{target} --- replaying crashes");
+        let (flags, dirs) = flags_and_dirs(Object::Crashes, &executable.name, target);
+        let entries = for_each_entry(&replay_opts, executable, target, flags, &dirs)?;
+
+        let mut new_crashes = String::new();
+        let mut n_new_crashes = 0;
+        let mut panic_message = None;
+        let mut system_out = String::new();
+        for entry in &entries {
+            let stderr = String::from_utf8_lossy(&entry.output);
+            if known_crashes.contains(&entry.path) {
+                let _ = writeln!(system_out, "Previously known crash: {}", entry.label);
+                continue;
+            }
+            n_new_crashes += 1;
+            if panic_message.is_none() {
+                panic_message = Some(extract_panic_message(&stderr)).filter(|s| !s.is_empty());
+            }
+            let _ = writeln!(
+                new_crashes,
+                "{}: {}\n{}",
+                entry.label,
+                match entry.outcome {
+                    Outcome::Failure => "crashed when replayed",
+                    Outcome::Timeout => "timed out when replayed",
+                    Outcome::Raw | Outcome::Success => "did not crash when replayed",
+                },
+                stderr.trim_end()
+            );
+        }
+
+        if n_new_crashes > 0 {
+            n_crashing += 1;
+            testcase.result = TestResult::Failure {
+                message: format!(
+                    "{n_new_crashes} new crash{}{}",
+                    if n_new_crashes == 1 { "" } else { "es" },
+                    panic_message
+                        .map(|panic_message| format!(": {panic_message}"))
+                        .unwrap_or_default()
+                ),
+                text: new_crashes,
+            };
+        }
+        testcase.system_out = Some(system_out).filter(|s| !s.is_empty());
+        testcases.push(testcase);
+    }
+
+    let n_errors = testcases
+        .iter()
+        .filter(|testcase| matches!(testcase.result, TestResult::Error { .. }))
+        .count();
+
+    let report = Path::new(opts.junit.as_deref().unwrap_or(CI_REPORT));
+    write_report(
+        report,
+        "test-fuzz",
+        &[TestSuite {
+            name: "test-fuzz".to_owned(),
+            testcases,
+        }],
+    )?;
+    eprintln!("Wrote JUnit report to `{}`", report.to_string_lossy());
+
+    ensure!(
+        n_crashing == 0,
+        "Found new crashes in {n_crashing} of {} targets",
+        executable_targets.len()
+    );
+    ensure!(
+        n_errors == 0,
+        "Could not fuzz {n_errors} of {} targets",
+        executable_targets.len()
+    );
+
+    Ok(())
+}
+
+/// Returns the paths of the target's crashes across all of its fuzzer instances.
+fn crash_files(krate: &str, target: &str) -> BTreeSet<PathBuf> {
+    crashes_directory_from_target(krate, target)
+        .iter()
+        .filter_map(|dir| read_dir(dir).ok())
+        .flatten()
+        .filter_map(Result::ok)
+        .filter(|entry| {
+            let file_name = entry.file_name();
+            file_name != "README.txt" && !file_name.to_string_lossy().starts_with('.')
+        })
+        .map(|entry| entry.path())
+        .collect()
+}
@@ -0,0 +1,163 @@
+use anyhow::{Context, Result};
+use internal::dirs::instance_directories;
+use std::{collections::BTreeMap, fs::read_to_string, path::Path};
+
+/// The contents of an AFL++ instance's `fuzzer_stats` file.
+///
+/// Each line of the file has the form `key : value`. Several keys were renamed in AFL++ 4.00
+/// (e.g., `paths_total` became `corpus_count`), so the accessors accept either name.
+#[derive(Clone, Debug, Default)]
+pub struct FuzzerStats {
+    fields: BTreeMap<String, String>,
+}
+
+impl FuzzerStats {
+    pub fn read(path: &Path) -> Result<Self> {
+        let contents = read_to_string(path)
+            .with_context(|| format!("`read_to_string` failed for `{}`", path.to_string_lossy()))?;
+        Ok(Self::parse(&contents))
+    }
+
+    pub fn parse(contents: &str) -> Self {
+        let fields = contents
+            .lines()
+            .filter_map(|line| line.split_once(':'))
+            .map(|(key, value)| (key.trim().to_owned(), value.trim().to_owned()))
+            .collect();
+        Self { fields }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.fields.get(key).map(String::as_str)
+    }
+
+    pub fn fields(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.fields
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+    }
+
+    /// Total number of executions
+    pub fn execs_done(&self) -> Option<u64> {
+        self.get_u64(&["execs_done"])
+    }
+
+    /// Executions per second, averaged over the instance's run
+    pub fn execs_per_sec(&self) -> Option<f64> {
+        self.get("execs_per_sec")
+            .and_then(|value| value.parse().ok())
+    }
+
+    /// Percentage of the coverage bitmap that is set
+    pub fn bitmap_cvg(&self) -> Option<f64> {
+        self.get("bitmap_cvg")
+            .and_then(|value| value.trim_end_matches('%').parse().ok())
+    }
+
+    /// Number of unique crashes the instance has saved
+    pub fn saved_crashes(&self) -> Option<u64> {
+        self.get_u64(&["saved_crashes", "unique_crashes"])
+    }
+
+    /// Number of unique hangs the instance has saved
+    pub fn saved_hangs(&self) -> Option<u64> {
+        self.get_u64(&["saved_hangs", "unique_hangs"])
+    }
+
+    /// Number of entries in the instance's queue
+    pub fn corpus_count(&self) -> Option<u64> {
+        self.get_u64(&["corpus_count", "paths_total"])
+    }
+
+    /// Number of queue entries found by the instance, i.e., not imported or taken from its input
+    pub fn corpus_found(&self) -> Option<u64> {
+        self.get_u64(&["corpus_found", "paths_found"])
+    }
+
+    /// Unix time of the instance's last new find, or zero if it has found nothing
+    pub fn last_find(&self) -> Option<u64> {
+        self.get_u64(&["last_find", "last_path"])
+    }
+
+    /// Unix time at which the file was last written
+    pub fn last_update(&self) -> Option<u64> {
+        self.get_u64(&["last_update"])
+    }
+
+    fn get_u64(&self, keys: &[&str]) -> Option<u64> {
+        keys.iter()
+            .find_map(|key| self.get(key))
+            .and_then(|value| value.parse().ok())
+    }
+}
+
+/// Reads the `fuzzer_stats` of every fuzzer instance of the target with output directory
+/// `output_dir`.
+///
+/// Instances that have not yet written a `fuzzer_stats` file are skipped.
+pub fn fuzzer_stats(output_dir: &Path) -> Vec<FuzzerStats> {
+    instance_fuzzer_stats(output_dir)
+        .into_iter()
+        .map(|(_, fuzzer_stats)| fuzzer_stats)
+        .collect()
+}
+
+/// Like [`fuzzer_stats`], but pairs each instance's `fuzzer_stats` with the instance's name (e.g.,
+/// `default`).
+pub fn instance_fuzzer_stats(output_dir: &Path) -> Vec<(String, FuzzerStats)> {
+    instance_directories(output_dir)
+        .into_iter()
+        .filter_map(|dir| {
+            let path = dir.join("fuzzer_stats");
+            if !path.exists() {
+                return None;
+            }
+            let instance = dir.file_name()?.to_string_lossy().into_owned();
+            let fuzzer_stats = FuzzerStats::read(&path).ok()?;
+            Some((instance, fuzzer_stats))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FuzzerStats;
+
+    #[allow(clippy::float_cmp)]
+    #[test]
+    fn parse() {
+        let stats = FuzzerStats::parse(
+            "start_time        : 1700000000\nlast_update       : 1700000600\nrun_time          : \
+             600\nfuzzer_pid        : 12345\nexecs_done        : 740736\nexecs_per_sec     : \
+             1234.56\ncorpus_count      : 42\ncorpus_found      : 40\nlast_find         : \
+             1700000300\nsaved_crashes     : 2\nsaved_hangs       : 1\nbitmap_cvg        : \
+             3.21%\ncommand_line      : afl-fuzz -i - -o out -- target --exact a::b\n",
+        );
+        assert_eq!(Some(740_736), stats.execs_done());
+        assert_eq!(Some(1234.56), stats.execs_per_sec());
+        assert_eq!(Some(42), stats.corpus_count());
+        assert_eq!(Some(40), stats.corpus_found());
+        assert_eq!(Some(2), stats.saved_crashes());
+        assert_eq!(Some(1), stats.saved_hangs());
+        assert_eq!(Some(3.21), stats.bitmap_cvg());
+        assert_eq!(Some(1_700_000_300), stats.last_find());
+        assert_eq!(Some(1_700_000_600), stats.last_update());
+        assert_eq!(
+            Some("afl-fuzz -i - -o out -- target --exact a::b"),
+            stats.get("command_line")
+        );
+    }
+
+    #[test]
+    fn parse_legacy_names() {
+        let stats = FuzzerStats::parse(
+            "paths_total : 7\npaths_found : 5\nlast_path : 0\nunique_crashes : 3\nunique_hangs : \
+             4\n",
+        );
+        assert_eq!(Some(7), stats.corpus_count());
+        assert_eq!(Some(5), stats.corpus_found());
+        assert_eq!(Some(0), stats.last_find());
+        assert_eq!(Some(3), stats.saved_crashes());
+        assert_eq!(Some(4), stats.saved_hangs());
+    }
+}
@@ -0,0 +1,318 @@
+use crate::{
+    BASE_ENVS, DEFAULT_TIMEOUT, ENTRY_SUFFIX, Executable, KrateTargetDir, TestFuzz, to_exec::ToExec,
+};
+use anyhow::{Context, Result, anyhow};
+use internal::dirs::instance_directories;
+use log::debug;
+use std::{
+    collections::HashSet,
+    fs::{File, read_dir},
+    path::{Path, PathBuf},
+    process::{Child, Stdio},
+    sync::mpsc::{Receiver, Sender, channel},
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+use subprocess::Redirection;
+
+#[derive(Clone, Copy)]
+enum Event {
+    Crash,
+    Hang,
+}
+
+impl Event {
+    const fn name(self) -> &'static str {
+        match self {
+            Self::Crash => "crash",
+            Self::Hang => "hang",
+        }
+    }
+}
+
+/// Runs the `--on-crash` and `--on-hang` commands for crashes and hangs that appear while fuzzing.
+///
+/// Crashes and hangs that exist when fuzzing starts are not reported.
+///
+/// New crashes and hangs are found on the caller's thread, which only lists directories. Replaying
+/// crashes to obtain their panic messages and running the commands happen on a worker thread, so
+/// that a slow replay does not stall the caller (e.g., `cargo test-fuzz`'s event loop).
+pub struct Hooks {
+    opts: TestFuzz,
+    /// Each target's executable, name, and output directory
+    targets: Vec<(Executable, String, PathBuf)>,
+    seen: HashSet<PathBuf>,
+    sender: Option<Sender<Notification>>,
+    worker: Option<JoinHandle<Result<()>>>,
+}
+
+/// A crash or hang for which a command should be run
+struct Notification {
+    event: Event,
+    command: String,
+    i_target: usize,
+    path: PathBuf,
+}
+
+impl Hooks {
+    /// `krate_target_dirs` must correspond to `executable_targets`.
+    pub fn new(
+        opts: &TestFuzz,
+        executable_targets: &[(Executable, String)],
+        krate_target_dirs: &[KrateTargetDir],
+    ) -> Self {
+        let targets = executable_targets
+            .iter()
+            .zip(krate_target_dirs)
+            .map(|((executable, target), (_, _, output_dir))| {
+                (executable.clone(), target.clone(), output_dir.clone())
+            })
+            .collect::<Vec<_>>();
+        let mut seen = HashSet::new();
+        for (_, _, output_dir) in &targets {
+            for (event, _) in commands(opts) {
+                seen.extend(files(event, output_dir));
+            }
+        }
+        Self {
+            opts: opts.clone(),
+            targets,
+            seen,
+            sender: None,
+            worker: None,
+        }
+    }
+
+    pub const fn enabled(opts: &TestFuzz) -> bool {
+        opts.on_crash.is_some() || opts.on_hang.is_some()
+    }
+
+    /// Sends the appropriate command for each crash or hang that has appeared since the last call
+    /// to the worker thread. The commands are not waited for.
+    ///
+    /// An error is returned if the worker thread failed, e.g., because a command could not be
+    /// spawned.
+    pub fn check(&mut self) -> Result<()> {
+        if self.worker.as_ref().is_some_and(JoinHandle::is_finished) {
+            return self.wait();
+        }
+
+        let mut notifications = Vec::new();
+        for (i_target, (_, _, output_dir)) in self.targets.iter().enumerate() {
+            for (event, command) in commands(&self.opts) {
+                for path in files(event, output_dir) {
+                    if self.seen.insert(path.clone()) {
+                        notifications.push(Notification {
+                            event,
+                            command: command.to_owned(),
+                            i_target,
+                            path,
+                        });
+                    }
+                }
+            }
+        }
+
+        for notification in notifications {
+            if self.sender().send(notification).is_err() {
+                // smoelius: The worker exited early. Its result explains why.
+                return self.wait();
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Waits for all sent commands to be run and to finish.
+    pub fn wait(&mut self) -> Result<()> {
+        drop(self.sender.take());
+        let Some(worker) = self.worker.take() else {
+            return Ok(());
+        };
+        worker
+            .join()
+            .map_err(|_| anyhow!("Hook worker thread panicked"))?
+    }
+
+    fn sender(&mut self) -> &Sender<Notification> {
+        if self.sender.is_none() {
+            let (sender, receiver) = channel();
+            let opts = self.opts.clone();
+            let targets = self.targets.clone();
+            self.worker = Some(thread::spawn(move || work(&opts, &targets, &receiver)));
+            self.sender = Some(sender);
+        }
+        #[allow(clippy::unwrap_used)]
+        self.sender.as_ref().unwrap()
+    }
+}
+
+/// Runs the command for each notification received, then waits for the commands to finish.
+fn work(
+    opts: &TestFuzz,
+    targets: &[(Executable, String, PathBuf)],
+    receiver: &Receiver<Notification>,
+) -> Result<()> {
+    let mut running = Vec::<Child>::new();
+
+    for Notification {
+        event,
+        command,
+        i_target,
+        path,
+    } in receiver
+    {
+        running.retain_mut(|child| child.try_wait().is_ok_and(|status| status.is_none()));
+
+        let (executable, target, _) = &targets[i_target];
+        let panic_message = match event {
+            Event::Crash => panic_message(opts, executable, target, &path)?,
+            // smoelius: Replaying a hang would likely just hang again.
+            Event::Hang => String::new(),
+        };
+        let mut command = opts.command(["sh", "-c", &command]);
+        command
+            .env("TEST_FUZZ_HOOK_EVENT", event.name())
+            .env("TEST_FUZZ_HOOK_CRATE", &executable.name)
+            .env("TEST_FUZZ_HOOK_TARGET", target)
+            .env("TEST_FUZZ_HOOK_FILE", &path)
+            .env("TEST_FUZZ_HOOK_PANIC_MESSAGE", panic_message)
+            .stdin(Stdio::null());
+        debug!("{command:?}");
+        let child = command
+            .spawn()
+            .with_context(|| format!("Could not spawn `{command:?}`"))?;
+        running.push(child);
+    }
+
+    for mut child in running {
+        let status = child
+            .wait()
+            .with_context(|| format!("`wait` failed for `{child:?}`"))?;
+        if !status.success() {
+            eprintln!("Warning: Hook command failed: {status}");
+        }
+    }
+
+    Ok(())
+}
+
+fn commands(opts: &TestFuzz) -> impl Iterator<Item = (Event, &str)> {
+    [
+        (Event::Crash, opts.on_crash.as_deref()),
+        (Event::Hang, opts.on_hang.as_deref()),
+    ]
+    .into_iter()
+    .filter_map(|(event, command)| command.map(|command| (event, command)))
+}
+
+/// Returns the crash or hang files in the instance directories of `output_dir`.
+fn files(event: Event, output_dir: &Path) -> Vec<PathBuf> {
+    let name = match event {
+        Event::Crash => "crashes",
+        Event::Hang => "hangs",
+    };
+    instance_directories(output_dir)
+        .iter()
+        .filter_map(|dir| read_dir(dir.join(name)).ok())
+        .flatten()
+        .filter_map(Result::ok)
+        .filter(|entry| {
+            let file_name = entry.file_name();
+            file_name != "README.txt" && !file_name.to_string_lossy().starts_with('.')
+        })
+        .map(|entry| entry.path())
+        .collect()
+}
+
+/// Replays `path` and returns the message of the resulting panic, or the empty string if the
+/// message could not be determined.
+fn panic_message(
+    opts: &TestFuzz,
+    executable: &Executable,
+    target: &str,
+    path: &Path,
+) -> Result<String> {
+    let file = File::open(path)
+        .with_context(|| format!("`open` failed for `{}`", path.to_string_lossy()))?;
+    let mut envs = BASE_ENVS.to_vec();
+    envs.extend([("AFL_QUIET", "1"), ("TEST_FUZZ_REPLAY", "1")]);
+    let exec = opts
+        .command([&executable.path])
+        .to_exec()
+        .env_extend(envs.iter().copied())
+        .args([
+            "--exact",
+            &(target.to_owned() + ENTRY_SUFFIX),
+            "--nocapture",
+        ])
+        .stdin(file)
+        .stdout(Redirection::Null)
+        .stderr(Redirection::Pipe);
+    debug!("{exec:?}");
+    let exec_str = format!("{exec:?}");
+    let mut job = exec
+        .start()
+        .with_context(|| format!("`start` failed for `{exec_str}`"))?;
+    let time = Duration::from_secs(opts.timeout.unwrap_or(DEFAULT_TIMEOUT));
+    let mut communicator = job
+        .communicate()
+        .with_context(|| format!("`communicate` failed for `{job:?}`"))?
+        .limit_time(time);
+    let mut stderr = Vec::new();
+    let result = communicator.read_to(std::io::sink(), &mut stderr);
+    if result.is_err() {
+        job.kill()
+            .with_context(|| format!("`kill` failed for `{job:?}`"))?;
+    }
+    let _ = job
+        .wait()
+        .with_context(|| format!("`wait` failed for `{job:?}`"))?;
+    if let Err(error) = result {
+        debug!("Could not read output of `{exec_str}`: {error}");
+        return Ok(String::new());
+    }
+    Ok(extract_panic_message(&String::from_utf8_lossy(&stderr)))
+}
+
+/// Extracts the message from a panic such as:
+///
+/// ```text
+/// thread 'main' panicked at src/lib.rs:1:2:
+/// message
+/// note: run with `RUST_BACKTRACE=1` environment variable to display a backtrace
+/// ```
+pub fn extract_panic_message(stderr: &str) -> String {
+    let mut lines = stderr.lines();
+    if !lines.any(|line| line.contains(" panicked at ")) {
+        return String::new();
+    }
+    lines
+        .take_while(|line| !line.starts_with("note: ") && !line.starts_with("stack backtrace:"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::extract_panic_message;
+
+    #[test]
+    fn panic_messages() {
+        assert_eq!(
+            "assertion failed: !x",
+            extract_panic_message(
+                "thread 'target_fuzz__::entry' panicked at tests/assert.rs:3:5:\nassertion \
+                 failed: !x\nnote: run with `RUST_BACKTRACE=1` environment variable to display a \
+                 backtrace\n"
+            )
+        );
+        assert_eq!(
+            "first line\nsecond line",
+            extract_panic_message(
+                "thread 'main' panicked at src/lib.rs:1:2:\nfirst line\nsecond line\n"
+            )
+        );
+        assert_eq!("", extract_panic_message("no panic here\n"));
+    }
+}
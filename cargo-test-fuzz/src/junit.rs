@@ -0,0 +1,302 @@
+use crate::{DEFAULT_TIMEOUT, Entry, Outcome, TestFuzz, hooks::extract_panic_message};
+use anyhow::{Context, Result};
+use std::{fmt::Write, fs::write, path::Path, time::Duration};
+
+/// A `<testsuite>` element of a `JUnit` XML report
+#[derive(Debug)]
+pub struct TestSuite {
+    pub name: String,
+    pub testcases: Vec<TestCase>,
+}
+
+/// A `<testcase>` element of a `JUnit` XML report
+#[derive(Debug)]
+pub struct TestCase {
+    pub name: String,
+    pub classname: String,
+    pub time: Duration,
+    pub result: TestResult,
+    pub system_out: Option<String>,
+    pub system_err: Option<String>,
+}
+
+#[derive(Debug)]
+pub enum TestResult {
+    Passed,
+    /// The test ran and failed, e.g., because a crash was found
+    Failure {
+        message: String,
+        text: String,
+    },
+    /// The test could not be run as intended, e.g., because it timed out
+    Error {
+        message: String,
+        text: String,
+    },
+}
+
+impl TestSuite {
+    /// Returns a testsuite for one target, with one testcase per entry. Failures and timeouts
+    /// carry the target's stderr, as do passing testcases that wrote to stderr.
+    pub fn from_entries(opts: &TestFuzz, krate: &str, target: &str, entries: &[Entry]) -> Self {
+        let testcases = entries
+            .iter()
+            .map(|entry| {
+                let stderr = strip_ansi_escapes::strip_str(String::from_utf8_lossy(&entry.output));
+                let result = match entry.outcome {
+                    Outcome::Raw | Outcome::Success => TestResult::Passed,
+                    Outcome::Failure => {
+                        let panic_message = extract_panic_message(&stderr);
+                        TestResult::Failure {
+                            message: if panic_message.is_empty() {
+                                "Failed".to_owned()
+                            } else {
+                                panic_message
+                            },
+                            text: stderr.clone(),
+                        }
+                    }
+                    Outcome::Timeout => TestResult::Error {
+                        message: format!(
+                            "Timed out after {}s",
+                            opts.timeout.unwrap_or(DEFAULT_TIMEOUT)
+                        ),
+                        text: stderr.clone(),
+                    },
+                };
+                let system_err = Some(stderr)
+                    .filter(|stderr| matches!(result, TestResult::Passed) && !stderr.is_empty());
+                TestCase {
+                    name: entry.label.clone(),
+                    classname: krate.to_owned(),
+                    time: entry.elapsed,
+                    result,
+                    system_out: None,
+                    system_err,
+                }
+            })
+            .collect();
+        Self {
+            name: target.to_owned(),
+            testcases,
+        }
+    }
+
+    fn count(&self, f: fn(&TestResult) -> bool) -> usize {
+        self.testcases
+            .iter()
+            .filter(|testcase| f(&testcase.result))
+            .count()
+    }
+
+    fn n_failures(&self) -> usize {
+        self.count(|result| matches!(result, TestResult::Failure { .. }))
+    }
+
+    fn n_errors(&self) -> usize {
+        self.count(|result| matches!(result, TestResult::Error { .. }))
+    }
+
+    fn time(&self) -> Duration {
+        self.testcases.iter().map(|testcase| testcase.time).sum()
+    }
+}
+
+pub fn write_report(path: &Path, name: &str, testsuites: &[TestSuite]) -> Result<()> {
+    write(path, report(name, testsuites))
+        .with_context(|| format!("`write` failed for `{}`", path.to_string_lossy()))
+}
+
+fn report(name: &str, testsuites: &[TestSuite]) -> String {
+    let mut xml = String::new();
+    let _ = writeln!(xml, r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+    let _ = writeln!(
+        xml,
+        r#"<testsuites name="{}" tests="{}" failures="{}" errors="{}">"#,
+        escape(name),
+        testsuites
+            .iter()
+            .map(|testsuite| testsuite.testcases.len())
+            .sum::<usize>(),
+        testsuites.iter().map(TestSuite::n_failures).sum::<usize>(),
+        testsuites.iter().map(TestSuite::n_errors).sum::<usize>(),
+    );
+    for testsuite in testsuites {
+        let _ = writeln!(
+            xml,
+            r#"  <testsuite name="{}" tests="{}" failures="{}" errors="{}" time="{:.3}">"#,
+            escape(&testsuite.name),
+            testsuite.testcases.len(),
+            testsuite.n_failures(),
+            testsuite.n_errors(),
+            testsuite.time().as_secs_f64(),
+        );
+        for testcase in &testsuite.testcases {
+            let _ = write!(
+                xml,
+                r#"    <testcase name="{}" classname="{}" time="{:.3}""#,
+                escape(&testcase.name),
+                escape(&testcase.classname),
+                testcase.time.as_secs_f64(),
+            );
+            if matches!(testcase.result, TestResult::Passed)
+                && testcase.system_out.is_none()
+                && testcase.system_err.is_none()
+            {
+                let _ = writeln!(xml, "/>");
+                continue;
+            }
+            let _ = writeln!(xml, ">");
+            match &testcase.result {
+                TestResult::Passed => {}
+                TestResult::Failure { message, text } => {
+                    let _ = writeln!(
+                        xml,
+                        r#"      <failure message="{}">{}</failure>"#,
+                        escape(message),
+                        escape(text)
+                    );
+                }
+                TestResult::Error { message, text } => {
+                    let _ = writeln!(
+                        xml,
+                        r#"      <error message="{}">{}</error>"#,
+                        escape(message),
+                        escape(text)
+                    );
+                }
+            }
+            if let Some(system_out) = &testcase.system_out {
+                let _ = writeln!(xml, "      <system-out>{}</system-out>", escape(system_out));
+            }
+            if let Some(system_err) = &testcase.system_err {
+                let _ = writeln!(xml, "      <system-err>{}</system-err>", escape(system_err));
+            }
+            let _ = writeln!(xml, "    </testcase>");
+        }
+        let _ = writeln!(xml, "  </testsuite>");
+    }
+    let _ = writeln!(xml, "</testsuites>");
+    xml
+}
+
+/// Escapes `s` for use in XML text or attribute values.
+///
+/// Control characters other than tab, newline, and carriage return cannot appear in XML 1.0
+/// documents, even escaped, so they are dropped. Targets' stderr can contain them, e.g., in ANSI
+/// escape sequences.
+fn escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            _ if c.is_control() => {}
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TestCase, TestResult, TestSuite, escape, report};
+    use crate::{Entry, Outcome, TestFuzz};
+    use std::{path::PathBuf, time::Duration};
+
+    #[test]
+    fn escapes() {
+        assert_eq!(
+            "&lt;a href=&quot;x&quot;&gt;&amp;&apos;&lt;/a&gt;\n[31mred[0m",
+            escape("<a href=\"x\">&'</a>\n\x1b[31mred\x1b[0m")
+        );
+    }
+
+    #[test]
+    fn xml() {
+        let testsuites = [TestSuite {
+            name: "assert".to_owned(),
+            testcases: vec![
+                TestCase {
+                    name: "target".to_owned(),
+                    classname: "assert".to_owned(),
+                    time: Duration::from_millis(1500),
+                    result: TestResult::Failure {
+                        message: "1 new crash".to_owned(),
+                        text: "assertion failed: !x".to_owned(),
+                    },
+                    system_out: None,
+                    system_err: None,
+                },
+                TestCase {
+                    name: "other".to_owned(),
+                    classname: "assert".to_owned(),
+                    time: Duration::from_millis(500),
+                    result: TestResult::Passed,
+                    system_out: None,
+                    system_err: None,
+                },
+            ],
+        }];
+        assert_eq!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites name="test-fuzz" tests="2" failures="1" errors="0">
+  <testsuite name="assert" tests="2" failures="1" errors="0" time="2.000">
+    <testcase name="target" classname="assert" time="1.500">
+      <failure message="1 new crash">assertion failed: !x</failure>
+    </testcase>
+    <testcase name="other" classname="assert" time="0.500"/>
+  </testsuite>
+</testsuites>
+"#,
+            report("test-fuzz", &testsuites)
+        );
+    }
+
+    #[test]
+    fn entries() {
+        let entry = |label: &str, outcome, output: &str| Entry {
+            label: label.to_owned(),
+            path: PathBuf::from(label),
+            outcome,
+            output: output.as_bytes().to_vec(),
+            elapsed: Duration::from_millis(100),
+        };
+        let testsuite = TestSuite::from_entries(
+            &TestFuzz::default(),
+            "assert",
+            "target",
+            &[
+                entry("a", Outcome::Success, ""),
+                entry("b", Outcome::Success, "Ret(true)\n"),
+                entry(
+                    "c",
+                    Outcome::Failure,
+                    "thread 'main' panicked at src/lib.rs:1:2:\n\x1b[1massertion failed\x1b[0m\n",
+                ),
+                entry("d", Outcome::Timeout, ""),
+            ],
+        );
+        assert_eq!("target", testsuite.name);
+        assert_eq!(1, testsuite.n_failures());
+        assert_eq!(1, testsuite.n_errors());
+        assert!(matches!(testsuite.testcases[0].result, TestResult::Passed));
+        assert_eq!(None, testsuite.testcases[0].system_err);
+        assert_eq!(
+            Some("Ret(true)\n"),
+            testsuite.testcases[1].system_err.as_deref()
+        );
+        assert!(matches!(
+            &testsuite.testcases[2].result,
+            TestResult::Failure { message, .. } if message == "assertion failed"
+        ));
+        assert!(matches!(
+            &testsuite.testcases[3].result,
+            TestResult::Error { message, .. } if message == "Timed out after 1s"
+        ));
+    }
+}
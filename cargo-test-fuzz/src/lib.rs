@@ -24,29 +24,64 @@ use cargo_metadata::{
 use clap::{ValueEnum, crate_version};
 use heck::ToKebabCase;
 use internal::dirs::{
-    corpus_directory_from_target, crashes_directory_from_target,
+    MAIN_INSTANCE, corpus_directory_from_target, crashes_directory_from_target,
     generic_args_directory_from_target, hangs_directory_from_target,
     impl_generic_args_directory_from_target, output_directory_from_target,
-    queue_directory_from_target, target_directory,
+    queue_directory_from_target, ret_directory_from_target, target_directory,
 };
 use log::debug;
 use mio::{Events, Interest, Poll, Token, unix::pipe::Receiver};
 use semver::{Version, VersionReq};
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::{HashSet, VecDeque},
+    collections::{BTreeMap, BTreeSet, HashSet, VecDeque},
     ffi::OsStr,
     fmt::{Debug, Formatter},
-    fs::{File, create_dir_all, read, read_dir, remove_dir_all},
+    fs::{File, create_dir_all, read, read_dir, read_to_string, remove_dir_all},
     io::{BufRead, IsTerminal, Read},
     iter,
     path::{Path, PathBuf},
     process::{Child as StdChild, Command, Stdio, exit},
-    sync::OnceLock,
-    time::Duration,
+    sync::{
+        OnceLock,
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        mpsc,
+    },
+    thread,
+    time::{Duration, Instant},
 };
 use strum_macros::Display;
-use subprocess::Redirection;
+use subprocess::{ExitStatus, Redirection};
+
+mod apply;
+use apply::apply_generic_args;
+
+mod batch;
+use batch::run_batches;
+
+mod changed;
+use changed::filter_changed_targets;
+
+mod ci;
+use ci::{CI_MAX_TOTAL_TIME, ci};
+
+mod fuzzer_stats;
+use fuzzer_stats::fuzzer_stats;
+
+mod hooks;
+use hooks::Hooks;
+
+mod junit;
+use junit::{TestSuite, write_report};
+
+mod metrics;
+use metrics::{append_snapshot, serve_metrics};
+
+mod scheduler;
+use scheduler::Scheduler;
+
+mod status;
+use status::status_table;
 
 mod to_exec;
 use to_exec::ToExec;
@@ -60,6 +95,18 @@ const DEFAULT_TIMEOUT: u64 = 1;
 
 const MILLIS_PER_SEC: u64 = 1_000;
 
+/// How often the status table is refreshed while fuzzing with the user interface
+const UI_STATUS_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How often the status table is printed while fuzzing without the user interface
+const NO_UI_STATUS_INTERVAL: Duration = Duration::from_mins(1);
+
+/// How often a snapshot is appended to the `--stats-file`
+const STATS_FILE_INTERVAL: Duration = Duration::from_mins(1);
+
+/// How often targets' crashes and hangs directories are checked for `--on-crash` and `--on-hang`
+const HOOKS_INTERVAL: Duration = Duration::from_secs(5);
+
 bitflags! {
     #[derive(Copy, Clone, Eq, PartialEq)]
     struct Flags: u8 {
@@ -68,6 +115,24 @@ bitflags! {
     }
 }
 
+/// The result of running a target on one file in [`for_each_entry`]
+struct Entry {
+    label: String,
+    path: PathBuf,
+    outcome: Outcome,
+    /// The target's output on stderr or, for [`Flags::RAW`] entries, the file's contents
+    output: Vec<u8>,
+    elapsed: Duration,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Outcome {
+    Raw,
+    Success,
+    Failure,
+    Timeout,
+}
+
 #[derive(Clone, Copy, Debug, Display, Deserialize, PartialEq, Eq, Serialize, ValueEnum)]
 #[remain::sorted]
 pub enum Object {
@@ -87,7 +152,12 @@ pub enum Object {
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 #[remain::sorted]
 pub struct TestFuzz {
+    pub apply_generic_args: bool,
     pub backtrace: bool,
+    pub batch: bool,
+    pub changed_since: Option<String>,
+    pub check_regressions: Option<Object>,
+    pub ci: bool,
     pub consolidate: bool,
     pub consolidate_all: bool,
     pub coverage: Option<Object>,
@@ -96,12 +166,17 @@ pub struct TestFuzz {
     pub exact: bool,
     pub exit_code: bool,
     pub features: Vec<String>,
+    pub jobs: Option<usize>,
+    pub junit: Option<String>,
     pub list: bool,
     pub manifest_path: Option<String>,
     pub max_total_time: Option<u64>,
+    pub metrics_addr: Option<String>,
     pub no_default_features: bool,
     pub no_run: bool,
     pub no_ui: bool,
+    pub on_crash: Option<String>,
+    pub on_hang: Option<String>,
     pub package: Option<String>,
     pub persistent: bool,
     pub pretty: bool,
@@ -110,8 +185,12 @@ pub struct TestFuzz {
     pub reset: bool,
     pub reset_all: bool,
     pub resume: bool,
+    pub retire_after: Option<u64>,
     pub run_until_crash: bool,
     pub slice: u64,
+    pub stats_file: Option<String>,
+    pub status: bool,
+    pub suggest_generic_args: bool,
     pub test: Option<String>,
     pub timeout: Option<u64>,
     pub verbose: bool,
@@ -213,6 +292,7 @@ impl TestFuzz {
 
     const fn include_fuzzing_instrumentation(&self) -> bool {
         let no_fuzzing_instrumentation = self.list
+            || self.suggest_generic_args
             || self.coverage.is_some()
             || matches!(
                 self.display,
@@ -228,7 +308,8 @@ impl TestFuzz {
             || matches!(
                 self.replay,
                 Some(Object::Corpus | Object::Crashes | Object::Hangs | Object::Queue)
-            );
+            )
+            || matches!(self.check_regressions, Some(Object::Corpus));
         !no_fuzzing_instrumentation
     }
 }
@@ -265,9 +346,12 @@ impl Debug for Executable {
 pub fn run(opts: TestFuzz) -> Result<()> {
     let opts = {
         let mut opts = opts;
-        if opts.exit_code {
+        if opts.exit_code || opts.ci {
             opts.no_ui = true;
         }
+        if opts.ci && opts.max_total_time.is_none() {
+            opts.max_total_time = Some(CI_MAX_TOTAL_TIME);
+        }
         opts
     };
 
@@ -293,6 +377,28 @@ fn run_without_exit_code(opts: &TestFuzz) -> Result<()> {
             object.to_string().to_kebab_case()
         );
     }
+    ensure!(
+        !opts.apply_generic_args || opts.suggest_generic_args,
+        "--apply-generic-args requires --suggest-generic-args"
+    );
+    ensure!(
+        !(opts.ci && opts.exit_code),
+        "--ci and --exit-code cannot be used together"
+    );
+    ensure!(
+        opts.junit.is_none()
+            || opts.ci
+            || opts.replay.is_some()
+            || opts.check_regressions.is_some(),
+        "--junit requires --ci, --replay, or --check-regressions"
+    );
+    if let Some(object) = opts.check_regressions {
+        ensure!(
+            matches!(object, Object::Corpus | Object::CorpusInstrumented),
+            "`--check-regressions {}` is invalid.",
+            object.to_string().to_kebab_case()
+        );
+    }
     if let Some(object) = opts.replay {
         ensure!(
             !matches!(object, Object::ImplGenericArgs | Object::GenericArgs),
@@ -308,9 +414,12 @@ fn run_without_exit_code(opts: &TestFuzz) -> Result<()> {
 
     let display = opts.display.is_some();
 
-    let replay = opts.replay.is_some();
+    let replay = opts.replay.is_some() || opts.check_regressions.is_some();
 
-    let executables = build(opts, coverage || display || replay)?;
+    let executables = build(
+        opts,
+        coverage || display || replay || opts.suggest_generic_args,
+    )?;
 
     let mut executable_targets = executable_targets(opts, &executables)?;
 
@@ -318,6 +427,14 @@ fn run_without_exit_code(opts: &TestFuzz) -> Result<()> {
         executable_targets = filter_executable_targets(opts, pat, &executable_targets);
     }
 
+    if let Some(rev) = &opts.changed_since {
+        executable_targets = filter_changed_targets(opts, rev, &executable_targets)?;
+        if executable_targets.is_empty() {
+            eprintln!("No fuzz targets are affected by the changes since `{rev}`");
+            return Ok(());
+        }
+    }
+
     check_test_fuzz_and_afl_versions(&executable_targets)?;
 
     if opts.list {
@@ -329,6 +446,19 @@ fn run_without_exit_code(opts: &TestFuzz) -> Result<()> {
         return Ok(());
     }
 
+    if opts.status {
+        let executable_targets = flatten_executable_targets(opts, executable_targets)?;
+        for line in status_table(&krate_target_dirs(&executable_targets)) {
+            println!("{line}");
+        }
+        return Ok(());
+    }
+
+    if opts.suggest_generic_args {
+        let executable_targets = flatten_executable_targets(opts, executable_targets)?;
+        return suggest_generic_args(opts, &executable_targets);
+    }
+
     if opts.consolidate_all || opts.reset_all {
         if opts.consolidate_all {
             consolidate(opts, &executable_targets)?;
@@ -355,6 +485,8 @@ fn run_without_exit_code(opts: &TestFuzz) -> Result<()> {
 
         let executable_targets = flatten_executable_targets(opts, executable_targets)?;
         let multiple = executable_targets.len() > 1;
+        let mut testsuites = Vec::new();
+        let mut regressions = false;
         let width = termsize::get().map(|size| size.cols as usize);
         for (executable, target) in &executable_targets {
             if multiple {
@@ -364,22 +496,48 @@ fn run_without_exit_code(opts: &TestFuzz) -> Result<()> {
                 );
             }
 
-            let (flags, dir) = None
+            let (flags, dirs) = None
                 .or_else(|| {
                     opts.coverage
-                        .map(|object| flags_and_dir(object, &executable.name, target))
+                        .map(|object| flags_and_dirs(object, &executable.name, target))
                 })
                 .or_else(|| {
                     opts.display
-                        .map(|object| flags_and_dir(object, &executable.name, target))
+                        .map(|object| flags_and_dirs(object, &executable.name, target))
                 })
                 .or_else(|| {
                     opts.replay
-                        .map(|object| flags_and_dir(object, &executable.name, target))
+                        .map(|object| flags_and_dirs(object, &executable.name, target))
+                })
+                .or_else(|| {
+                    opts.check_regressions
+                        .map(|object| flags_and_dirs(object, &executable.name, target))
                 })
-                .unwrap_or_else(|| (Flags::empty(), PathBuf::default()));
+                .unwrap_or_else(|| (Flags::empty(), Vec::new()));
+
+            let entries = for_each_entry(opts, executable, target, flags, &dirs)?;
+
+            if opts.check_regressions.is_some() {
+                regressions |= entries
+                    .iter()
+                    .any(|entry| matches!(entry.outcome, Outcome::Failure | Outcome::Timeout));
+                ensure!(!regressions || opts.junit.is_some(), "Found regressions");
+            }
+
+            if opts.junit.is_some() {
+                testsuites.push(TestSuite::from_entries(
+                    opts,
+                    &executable.name,
+                    target,
+                    &entries,
+                ));
+            }
+        }
 
-            for_each_entry(opts, executable, target, flags, &dir)?;
+        if let Some(path) = &opts.junit {
+            write_report(Path::new(path), "test-fuzz", &testsuites)?;
+            eprintln!("Wrote JUnit report to `{path}`");
+            ensure!(!regressions, "Found regressions");
         }
 
         if coverage {
@@ -418,6 +576,10 @@ Wrote lcov to `lcov.info`. To view it as html, try running:
 
     let executable_targets = flatten_executable_targets(opts, executable_targets)?;
 
+    if opts.ci {
+        return ci(opts, &executable_targets);
+    }
+
     fuzz(opts, &executable_targets)
 }
 
@@ -772,11 +934,11 @@ fn consolidate(opts: &TestFuzz, executable_targets: &[(Executable, Vec<String>)]
 
         for target in targets {
             let corpus_dir = corpus_directory_from_target(&executable.name, target);
-            let crashes_dir = crashes_directory_from_target(&executable.name, target);
-            let hangs_dir = hangs_directory_from_target(&executable.name, target);
-            let queue_dir = queue_directory_from_target(&executable.name, target);
+            let crashes_dirs = crashes_directory_from_target(&executable.name, target);
+            let hangs_dirs = hangs_directory_from_target(&executable.name, target);
+            let queue_dirs = queue_directory_from_target(&executable.name, target);
 
-            for dir in &[crashes_dir, hangs_dir, queue_dir] {
+            for dir in crashes_dirs.iter().chain(&hangs_dirs).chain(&queue_dirs) {
                 for entry in read_dir(dir)
                     .with_context(|| format!("`read_dir` failed for `{}`", dir.to_string_lossy()))?
                 {
@@ -830,12 +992,151 @@ fn reset(opts: &TestFuzz, executable_targets: &[(Executable, Vec<String>)]) -> R
     Ok(())
 }
 
+fn suggest_generic_args(
+    opts: &TestFuzz,
+    executable_targets: &[(Executable, String)],
+) -> Result<()> {
+    for (executable, target) in executable_targets {
+        let generic_args_dir = generic_args_directory_from_target(&executable.name, target);
+        // smoelius: A target that was not called during tests has no recorded generic args. That
+        // should not prevent suggestions for the other targets.
+        if !generic_args_dir.exists() {
+            eprintln!(
+                "Warning: Could not find `{}`. Did you remember to run `cargo test`?",
+                generic_args_dir.to_string_lossy()
+            );
+            continue;
+        }
+        let nameable =
+            |args: &String| format!(r#""{}""#, nameable_type_names(args, &executable.name));
+        let impl_generic_args = recorded_generic_args(&impl_generic_args_directory_from_target(
+            &executable.name,
+            target,
+        ))?
+        .iter()
+        .map(nameable)
+        .collect::<Vec<_>>();
+        let generic_args = recorded_generic_args(&generic_args_dir)?
+            .iter()
+            .map(nameable)
+            .collect::<Vec<_>>();
+
+        // smoelius: Several recorded instantiations of the function's own type parameters become a
+        // list, which generates one fuzz target per instantiation.
+        let generic_args = match generic_args.as_slice() {
+            [] => None,
+            [args] => Some(format!("generic_args = {args}")),
+            _ => Some(format!("generic_args = [{}]", generic_args.join(", "))),
+        };
+
+        // smoelius: Non-generic targets record empty generic args, which are filtered out by
+        // `recorded_generic_args`.
+        if impl_generic_args.is_empty() && generic_args.is_none() {
+            continue;
+        }
+
+        // smoelius: `impl_generic_args` does not accept a list. Moreover, the impl generic args and
+        // generic args of a call are recorded in separate files. So when multiple impl
+        // instantiations were recorded, there is no way to tell which generic args go with which
+        // impl generic args.
+        if impl_generic_args.len() > 1 {
+            println!("{target}: multiple impl instantiations recorded; choose one of:");
+            for impl_args in &impl_generic_args {
+                let args = iter::once(format!("impl_generic_args = {impl_args}"))
+                    .chain(generic_args.clone())
+                    .collect::<Vec<_>>();
+                println!("    #[test_fuzz({})]", args.join(", "));
+            }
+            if opts.apply_generic_args {
+                eprintln!(
+                    "Warning: Not rewriting the attribute of `{target}`, which has multiple impl \
+                     instantiations"
+                );
+            }
+            continue;
+        }
+
+        let args = impl_generic_args
+            .first()
+            .map(|impl_args| format!("impl_generic_args = {impl_args}"))
+            .into_iter()
+            .chain(generic_args)
+            .collect::<Vec<_>>();
+        println!("{target}: #[test_fuzz({})]", args.join(", "));
+
+        if opts.apply_generic_args && !apply_generic_args(opts, executable, target, &args)? {
+            eprintln!(
+                "Warning: Could not rewrite the attribute of `{target}`. Is `test-fuzz` up to \
+                 date?"
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the distinct, nonempty generic args recorded in `dir`, or an empty set if `dir` does
+/// not exist.
+fn recorded_generic_args(dir: &Path) -> Result<BTreeSet<String>> {
+    let mut generic_args = BTreeSet::new();
+
+    if !dir.exists() {
+        return Ok(generic_args);
+    }
+
+    for entry in read_dir(dir)
+        .with_context(|| format!("`read_dir` failed for `{}`", dir.to_string_lossy()))?
+    {
+        let entry =
+            entry.with_context(|| format!("`read_dir` failed for `{}`", dir.to_string_lossy()))?;
+        let path = entry.path();
+        if entry.file_name() == "README.txt" {
+            continue;
+        }
+        let args = read_to_string(&path)
+            .with_context(|| format!("`read_to_string` failed for `{}`", path.to_string_lossy()))?;
+        if !args.is_empty() {
+            generic_args.insert(args);
+        }
+    }
+
+    Ok(generic_args)
+}
+
+/// Rewrites the paths in `type_names`, which were obtained from [`std::any::type_name`], so that
+/// they can be named from within `krate`. For example, `alloc::string::String` becomes
+/// `std::string::String`, and `krate::Foo` becomes `crate::Foo`.
+fn nameable_type_names(type_names: &str, krate: &str) -> String {
+    let mut result = String::new();
+    let mut rest = type_names;
+    while !rest.is_empty() {
+        let at_path_start =
+            !result.ends_with(|c: char| c.is_alphanumeric() || c == '_' || c == ':');
+        let replacement = [(krate, "crate"), ("alloc", "std"), ("core", "std")]
+            .into_iter()
+            .find_map(|(from, to)| {
+                rest.strip_prefix(from)
+                    .filter(|suffix| at_path_start && suffix.starts_with("::"))
+                    .map(|suffix| (to, suffix))
+            });
+        if let Some((to, suffix)) = replacement {
+            result.push_str(to);
+            rest = suffix;
+        } else {
+            let mut chars = rest.chars();
+            result.extend(chars.next());
+            rest = chars.as_str();
+        }
+    }
+    result
+}
+
 #[allow(clippy::panic)]
-fn flags_and_dir(object: Object, krate: &str, target: &str) -> (Flags, PathBuf) {
+fn flags_and_dirs(object: Object, krate: &str, target: &str) -> (Flags, Vec<PathBuf>) {
     match object {
         Object::Corpus | Object::CorpusInstrumented => (
             Flags::REQUIRES_CARGO_TEST,
-            corpus_directory_from_target(krate, target),
+            vec![corpus_directory_from_target(krate, target)],
         ),
         Object::Crashes | Object::CrashesInstrumented => {
             (Flags::empty(), crashes_directory_from_target(krate, target))
@@ -848,11 +1149,11 @@ fn flags_and_dir(object: Object, krate: &str, target: &str) -> (Flags, PathBuf)
         }
         Object::ImplGenericArgs => (
             Flags::REQUIRES_CARGO_TEST | Flags::RAW,
-            impl_generic_args_directory_from_target(krate, target),
+            vec![impl_generic_args_directory_from_target(krate, target)],
         ),
         Object::GenericArgs => (
             Flags::REQUIRES_CARGO_TEST | Flags::RAW,
-            generic_args_directory_from_target(krate, target),
+            vec![generic_args_directory_from_target(krate, target)],
         ),
     }
 }
@@ -863,18 +1164,20 @@ fn for_each_entry(
     executable: &Executable,
     target: &str,
     flags: Flags,
-    dir: &Path,
-) -> Result<()> {
-    ensure!(
-        dir.exists(),
-        "Could not find `{}`{}",
-        dir.to_string_lossy(),
-        if flags.contains(Flags::REQUIRES_CARGO_TEST) {
-            ". Did you remember to run `cargo test`?"
-        } else {
-            ""
-        }
-    );
+    dirs: &[PathBuf],
+) -> Result<Vec<Entry>> {
+    for dir in dirs {
+        ensure!(
+            dir.exists(),
+            "Could not find `{}`{}",
+            dir.to_string_lossy(),
+            if flags.contains(Flags::REQUIRES_CARGO_TEST) {
+                ". Did you remember to run `cargo test`?"
+            } else {
+                ""
+            }
+        );
+    }
 
     let mut envs = BASE_ENVS.to_vec();
     envs.push(("AFL_QUIET", "1"));
@@ -884,7 +1187,7 @@ fn for_each_entry(
     if opts.display.is_some() {
         envs.push(("TEST_FUZZ_DISPLAY", "1"));
     }
-    if opts.replay.is_some() {
+    if opts.replay.is_some() || opts.check_regressions.is_some() {
         envs.push(("TEST_FUZZ_REPLAY", "1"));
     }
     if opts.backtrace {
@@ -903,111 +1206,194 @@ fn for_each_entry(
     .map(String::from)
     .collect();
 
+    let ret_dir = ret_directory_from_target(&executable.name, target);
+
+    let mut results = Vec::new();
     let mut nonempty = false;
     let mut failure = false;
     let mut timeout = false;
     let mut output = false;
 
-    for entry in read_dir(dir)
-        .with_context(|| format!("`read_dir` failed for `{}`", dir.to_string_lossy()))?
-    {
-        let entry =
-            entry.with_context(|| format!("`read_dir` failed for `{}`", dir.to_string_lossy()))?;
-        let path = entry.path();
-        let mut file = File::open(&path)
-            .with_context(|| format!("`open` failed for `{}`", path.to_string_lossy()))?;
+    let mut entries = Vec::new();
+    for dir in dirs {
+        for entry in read_dir(dir)
+            .with_context(|| format!("`read_dir` failed for `{}`", dir.to_string_lossy()))?
+        {
+            let entry = entry
+                .with_context(|| format!("`read_dir` failed for `{}`", dir.to_string_lossy()))?;
+            entries.push((dir, entry));
+        }
+    }
+    // smoelius: Sort the entries so that they are run, and their results printed, in a predictable
+    // order.
+    entries.sort_by_key(|(dir, entry)| (*dir, entry.file_name()));
+
+    let mut jobs = Vec::new();
+    for (dir, entry) in entries {
         let file_name = entry.file_name().to_string_lossy().into_owned();
 
         if file_name == "README.txt" || file_name == ".state" {
             continue;
         }
 
-        let (buffer, status) = if flags.contains(Flags::RAW) {
-            let mut buffer = Vec::new();
-            file.read_to_end(&mut buffer).with_context(|| {
-                format!("`read_to_end` failed for `{}`", path.to_string_lossy())
-            })?;
-            (buffer, None)
+        // smoelius: When entries come from multiple fuzzer instances, qualify each file name with
+        // its instance's name (e.g., `secondary1/id:000000,...`), since file names can collide.
+        let label = if dirs.len() > 1 {
+            dir.parent().and_then(Path::file_name).map_or_else(
+                || file_name.clone(),
+                |instance| format!("{}/{file_name}", instance.to_string_lossy()),
+            )
         } else {
-            let exec = opts
-                .command([&executable.path])
-                .to_exec()
-                .env_extend(envs.iter().copied())
-                .args(args.iter().map(String::as_str))
-                .stdin(file)
-                .stdout(Redirection::Null)
-                .stderr(Redirection::Pipe);
-            debug!("{exec:?}");
-            let exec_str = format!("{exec:?}");
-            let mut job = exec
-                .start()
-                .with_context(|| format!("`start` failed for `{exec_str}`"))?;
-            let secs = opts.timeout.unwrap_or(DEFAULT_TIMEOUT);
-            let time = Duration::from_secs(secs);
-            let communicator = job
-                .communicate()
-                .with_context(|| format!("`communicate` failed for `{job:?}`"))?;
-            let mut communicator = communicator.limit_time(time);
-            let mut stderr_buf = Vec::new();
-            match communicator.read_to(std::io::sink(), &mut stderr_buf) {
-                Ok(()) => {
-                    let status = job
-                        .wait()
-                        .with_context(|| format!("`wait` failed for `{job:?}`"))?;
-                    (stderr_buf, Some(status))
-                }
-                Err(error) => {
-                    job.kill()
-                        .with_context(|| format!("`kill` failed for `{job:?}`"))?;
-                    if error.kind() != std::io::ErrorKind::TimedOut {
-                        return Err(anyhow!(error));
-                    }
-                    let _ = job
-                        .wait()
-                        .with_context(|| format!("`wait` failed for `{job:?}`"))?;
-                    (stderr_buf, None)
-                }
-            }
+            file_name.clone()
         };
 
-        print!("{file_name}: ");
-        if let Some(last) = buffer.last() {
-            print!("{}", String::from_utf8_lossy(&buffer));
-            if last != &b'\n' {
-                println!();
+        // smoelius: When checking for regressions, only corpus files with recorded outcomes can be
+        // checked. Corpus files written by, e.g., `auto_generate` have none.
+        let ret_path = ret_dir.join(&file_name);
+        if opts.check_regressions.is_some() && !ret_path.exists() {
+            continue;
+        }
+
+        jobs.push(Job {
+            path: entry.path(),
+            label,
+            ret_path,
+        });
+    }
+
+    ensure!(
+        opts.cpus != Some(0),
+        "Number of cpus must be greater than zero"
+    );
+    // smoelius: With `--batch`, run all of a directory's files in one process where possible, since
+    // process startup can dominate the time to run a file. Checking regressions requires a per-file
+    // environment variable, and a target built with `__persistent` reads only standard input, so
+    // neither can be batched. Files that a batch does not finish, e.g., because one times out, are
+    // run in their own processes below. So are files that panicked, since an earlier file could
+    // have left the process in a state that caused the panic.
+    let mut pending = BTreeMap::new();
+    if opts.batch
+        && !flags.contains(Flags::RAW)
+        && opts.check_regressions.is_none()
+        && !opts.persistent
+    {
+        let batch_results = run_batches(opts, executable, &envs, &args, &jobs)?;
+        for (i, result) in batch_results.into_iter().enumerate() {
+            if let Some((buffer, status, elapsed)) = result
+                && status.success()
+            {
+                pending.insert(i, Ok((buffer, Some(status), elapsed)));
             }
-            output = true;
         }
-        if flags.contains(Flags::RAW) {
-            // No subprocess; nothing to check.
-        } else {
-            status.map_or_else(
-                || {
-                    println!("Timeout");
-                    timeout = true;
-                },
-                |status| {
-                    if buffer.is_empty() {
-                        println!("{status:?}");
+    }
+    let unbatched = (0..jobs.len())
+        .filter(|i| !pending.contains_key(i))
+        .collect::<Vec<_>>();
+
+    let n_workers = n_cpus(opts).max(1).min(unbatched.len().max(1));
+
+    // smoelius: Workers take jobs in order and send back their results, which are printed in order
+    // as they become available. If one job fails, `abort` tells the workers to stop.
+    let next = AtomicUsize::new(0);
+    let abort = AtomicBool::new(false);
+    let (sender, receiver) = mpsc::channel();
+
+    thread::scope(|scope| -> Result<()> {
+        let (jobs, unbatched, envs, args, next, abort) =
+            (&jobs, &unbatched, &envs, &args, &next, &abort);
+        for _ in 0..n_workers {
+            let sender = sender.clone();
+            scope.spawn(move || {
+                while !abort.load(Ordering::Relaxed) {
+                    let Some(&i) = unbatched.get(next.fetch_add(1, Ordering::Relaxed)) else {
+                        break;
+                    };
+                    let job = &jobs[i];
+                    let started = Instant::now();
+                    let result = run_entry(opts, executable, envs, args, flags, job)
+                        .map(|(buffer, status)| (buffer, status, started.elapsed()));
+                    if sender.send((i, result)).is_err() {
+                        break;
                     }
-                    failure |= !status.success();
-                },
-            );
+                }
+            });
         }
+        drop(sender);
 
-        nonempty = true;
-    }
+        let mut receiver = receiver.into_iter();
+        loop {
+            while let Some(result) = pending.remove(&results.len()) {
+                let (buffer, status, elapsed) =
+                    result.inspect_err(|_| abort.store(true, Ordering::Relaxed))?;
+                let Job { path, label, .. } = jobs[results.len()].clone();
+
+                print!("{label}: ");
+                if let Some(last) = buffer.last() {
+                    print!("{}", String::from_utf8_lossy(&buffer));
+                    if last != &b'\n' {
+                        println!();
+                    }
+                    output = true;
+                }
+                let outcome = if flags.contains(Flags::RAW) {
+                    // No subprocess; nothing to check.
+                    Outcome::Raw
+                } else {
+                    status.map_or_else(
+                        || {
+                            println!("Timeout");
+                            timeout = true;
+                            Outcome::Timeout
+                        },
+                        |status| {
+                            if buffer.is_empty() {
+                                println!("{status:?}");
+                            }
+                            if status.success() {
+                                Outcome::Success
+                            } else {
+                                failure = true;
+                                Outcome::Failure
+                            }
+                        },
+                    )
+                };
+
+                results.push(Entry {
+                    label,
+                    path,
+                    outcome,
+                    output: buffer,
+                    elapsed,
+                });
+
+                nonempty = true;
+            }
+            let Some((i, result)) = receiver.next() else {
+                break;
+            };
+            pending.insert(i, result);
+        }
+
+        Ok(())
+    })?;
 
     assert!(!(!nonempty && (failure || timeout || output)));
 
     if !nonempty {
         eprintln!("Nothing to {}.", present_participle(opts));
-        return Ok(());
+        return Ok(results);
+    }
+
+    // smoelius: Regressions are reported by the caller, so that a `--junit` report can be written
+    // first.
+    if opts.check_regressions.is_some() {
+        return Ok(results);
     }
 
     if !failure && !timeout && !output {
         eprintln!("No output on stderr detected.");
-        return Ok(());
+        return Ok(results);
     }
 
     if (failure || timeout) && opts.coverage.is_none() && opts.replay.is_none() {
@@ -1022,10 +1408,92 @@ fn for_each_entry(
                 unreachable!()
             }
         );
-        return Ok(());
+        return Ok(results);
     }
 
-    Ok(())
+    Ok(results)
+}
+
+/// A file for [`for_each_entry`] to run the target on
+#[derive(Clone)]
+struct Job {
+    path: PathBuf,
+    label: String,
+    ret_path: PathBuf,
+}
+
+/// Runs the target on one file or, if `flags` contains [`Flags::RAW`], reads the file. Returns the
+/// target's output on stderr (or the file's contents) and the target's exit status, or `None` if
+/// the target timed out.
+fn run_entry(
+    opts: &TestFuzz,
+    executable: &Executable,
+    envs: &[(&str, &str)],
+    args: &[String],
+    flags: Flags,
+    job: &Job,
+) -> Result<(Vec<u8>, Option<ExitStatus>)> {
+    let path = &job.path;
+    let mut file = File::open(path)
+        .with_context(|| format!("`open` failed for `{}`", path.to_string_lossy()))?;
+
+    if flags.contains(Flags::RAW) {
+        let mut buffer = Vec::new();
+        file.read_to_end(&mut buffer)
+            .with_context(|| format!("`read_to_end` failed for `{}`", path.to_string_lossy()))?;
+        return Ok((buffer, None));
+    }
+
+    let mut exec = opts
+        .command([&executable.path])
+        .to_exec()
+        .env_extend(envs.iter().copied())
+        .args(args.iter().map(String::as_str))
+        .stdin(file)
+        .stdout(Redirection::Null)
+        .stderr(Redirection::Pipe);
+    if opts.check_regressions.is_some() {
+        exec = exec.env("TEST_FUZZ_EXPECTED_RET", &job.ret_path);
+    }
+    debug!("{exec:?}");
+    let exec_str = format!("{exec:?}");
+    let mut job = exec
+        .start()
+        .with_context(|| format!("`start` failed for `{exec_str}`"))?;
+    let secs = opts.timeout.unwrap_or(DEFAULT_TIMEOUT);
+    let time = Duration::from_secs(secs);
+    let communicator = job
+        .communicate()
+        .with_context(|| format!("`communicate` failed for `{job:?}`"))?;
+    let mut communicator = communicator.limit_time(time);
+    let mut stderr_buf = Vec::new();
+    match communicator.read_to(std::io::sink(), &mut stderr_buf) {
+        Ok(()) => {
+            let status = job
+                .wait()
+                .with_context(|| format!("`wait` failed for `{job:?}`"))?;
+            Ok((stderr_buf, Some(status)))
+        }
+        Err(error) => {
+            job.kill()
+                .with_context(|| format!("`kill` failed for `{job:?}`"))?;
+            if error.kind() != std::io::ErrorKind::TimedOut {
+                return Err(anyhow!(error));
+            }
+            let _ = job
+                .wait()
+                .with_context(|| format!("`wait` failed for `{job:?}`"))?;
+            Ok((stderr_buf, None))
+        }
+    }
+}
+
+/// Returns `--cpus`, or all but one cpu by default, but no more than the number of cpus.
+fn n_cpus(opts: &TestFuzz) -> usize {
+    std::cmp::min(
+        opts.cpus.unwrap_or_else(|| num_cpus::get() - 1),
+        num_cpus::get(),
+    )
 }
 
 fn present_participle(opts: &TestFuzz) -> String {
@@ -1045,6 +1513,12 @@ fn present_participle(opts: &TestFuzz) -> String {
         }
         actions.push_str("replay");
     }
+    if opts.check_regressions.is_some() {
+        if !actions.is_empty() {
+            actions.push('/');
+        }
+        actions.push_str("check");
+    }
     actions
 }
 
@@ -1070,16 +1544,44 @@ fn flatten_executable_targets(
     Ok(executable_targets)
 }
 
+fn krate_targets(
+    executable_targets: &[(Executable, String)],
+) -> impl Iterator<Item = (&str, &str)> {
+    executable_targets
+        .iter()
+        .map(|(executable, target)| (executable.name.as_str(), target.as_str()))
+}
+
+/// A target's crate name, target name, and output directory
+type KrateTargetDir = (String, String, PathBuf);
+
+/// Determines each target's output directory. This runs `cargo metadata` for each target, so it
+/// should be done once, e.g., before fuzzing starts, rather than each time the directories are
+/// read.
+fn krate_target_dirs(executable_targets: &[(Executable, String)]) -> Vec<KrateTargetDir> {
+    krate_targets(executable_targets)
+        .map(|(krate, target)| {
+            (
+                krate.to_owned(),
+                target.to_owned(),
+                output_directory_from_target(krate, target),
+            )
+        })
+        .collect()
+}
+
 struct Config {
     ui: bool,
     sufficient_cpus: bool,
     first_run: bool,
+    remaining_time: Option<u64>,
 }
 
 struct Child {
     exec: String,
     target: String,
     popen: StdChild,
+    started: Instant,
     receiver: Receiver,
     unprinted_data: Vec<u8>,
     output_buffer: VecDeque<String>,
@@ -1116,7 +1618,12 @@ impl Child {
         }
     }
 
-    fn refresh(opts: &TestFuzz, n_children: usize, children: &mut [Option<Self>]) {
+    fn refresh(
+        opts: &TestFuzz,
+        status: &[String],
+        n_children: usize,
+        children: &mut [Option<Self>],
+    ) {
         if opts.no_ui {
             return;
         }
@@ -1129,10 +1636,23 @@ impl Child {
         let cols = cols as usize;
 
         // smoelius: `n_children` lines for dividers plus one line at the bottom of the terminal
-        // to hold the cursor.
+        // to hold the cursor. The status table is shown only if there is room for it and at least
+        // one line per child.
         let Some(n_available_rows) = rows.checked_sub(n_children + 1) else {
             return;
         };
+        let status = if n_available_rows >= status.len() + n_children {
+            status
+        } else {
+            &[]
+        };
+        let n_available_rows = n_available_rows - status.len();
+
+        for line in status {
+            print!("{}", prefix_with_width(line, cols));
+            clear_to_end_of_line();
+            println!();
+        }
 
         let children = children.iter_mut().flatten().collect::<Vec<_>>();
 
@@ -1210,14 +1730,30 @@ fn fuzz(opts: &TestFuzz, executable_targets: &[(Executable, String)]) -> Result<
 
     auto_generate_corpora(opts, executable_targets)?;
 
+    let krate_target_dirs = krate_target_dirs(executable_targets);
+
+    if let Some(addr) = &opts.metrics_addr {
+        serve_metrics(addr, krate_target_dirs.clone())?;
+    }
+
     let mut config = Config {
         ui: !opts.no_ui,
         sufficient_cpus: true,
         first_run: true,
+        remaining_time: None,
     };
 
-    if let (false, [(executable, target)]) = (opts.exit_code, executable_targets) {
-        let mut command = fuzz_command(opts, &config, executable, target);
+    let n_jobs = opts.jobs.unwrap_or(1);
+
+    ensure!(n_jobs >= 1, "Number of jobs must be greater than zero");
+
+    // smoelius: A `--stats-file` is written and hooks are run from the event loop below, so the
+    // single-target case goes through the event loop when either is requested.
+    let event_loop_required =
+        opts.exit_code || n_jobs > 1 || opts.stats_file.is_some() || Hooks::enabled(opts);
+
+    if let (false, [(executable, target)]) = (event_loop_required, executable_targets) {
+        let mut command = fuzz_command(opts, &config, executable, target, 0);
         let status = command
             .status()
             .with_context(|| format!("Could not get status of `{command:?}`"))?;
@@ -1225,84 +1761,125 @@ fn fuzz(opts: &TestFuzz, executable_targets: &[(Executable, String)]) -> Result<
         return Ok(());
     }
 
-    let n_cpus = std::cmp::min(
-        opts.cpus.unwrap_or_else(|| num_cpus::get() - 1),
-        num_cpus::get(),
-    );
+    let n_cpus = n_cpus(opts);
 
     ensure!(n_cpus >= 1, "Number of cpus must be greater than zero");
 
-    config.sufficient_cpus = n_cpus >= executable_targets.len();
+    // smoelius: Each target is fuzzed by `n_jobs` instances: one main instance and `n_jobs - 1`
+    // secondary instances, all sharing the target's output directory.
+    let instances = executable_targets
+        .iter()
+        .flat_map(|(executable, target)| {
+            (0..n_jobs).map(move |instance| (executable, target, instance))
+        })
+        .collect::<Vec<_>>();
+
+    config.sufficient_cpus = n_cpus >= instances.len();
 
     if !config.sufficient_cpus {
         ensure!(
-            opts.max_total_time.is_none(),
-            "--max-total-time cannot be used when number of cpus ({n_cpus}) is less than number \
-             of fuzz targets ({})",
-            executable_targets.len()
+            n_jobs == 1,
+            "--jobs cannot be used when number of cpus ({n_cpus}) is less than number of fuzzer \
+             instances ({})",
+            instances.len()
         );
 
         eprintln!(
-            "Number of cpus ({n_cpus}) is less than number of fuzz targets ({}); fuzzing each for \
-             {} seconds",
+            "Number of cpus ({n_cpus}) is less than number of fuzz targets ({}); fuzzing in \
+             slices of {} seconds, favoring targets that are still finding new inputs",
             executable_targets.len(),
             opts.slice
         );
     }
 
+    // smoelius: With sufficient cpus, `--max-total-time` is passed to each fuzzer instance. With
+    // insufficient cpus, it bounds the whole campaign, and slices are shortened to fit within it.
+    let deadline = opts
+        .max_total_time
+        .filter(|_| !config.sufficient_cpus)
+        .map(|secs| Instant::now() + Duration::from_secs(secs));
+    let mut scheduler = Scheduler::new(instances.len(), opts.retire_after);
+
     let mut n_children = 0;
     let mut i_task = 0;
-    let mut executable_targets_iter = executable_targets.iter().cycle();
     let mut poll = Poll::new().with_context(|| "`Poll::new` failed")?;
     let mut events = Events::with_capacity(128);
-    let mut children = vec![(); executable_targets.len()]
+    let mut children = vec![(); instances.len()]
         .into_iter()
         .map(|()| None::<Child>)
         .collect::<Vec<_>>();
-    let mut i_target_prev = executable_targets.len();
+    let mut i_instance_prev = instances.len();
+    let status_interval = if opts.no_ui {
+        NO_UI_STATUS_INTERVAL
+    } else {
+        UI_STATUS_INTERVAL
+    };
+    let mut status = Vec::new();
+    let mut status_refreshed: Option<Instant> = None;
+    let mut stats_file_written: Option<Instant> = None;
+    let mut hooks = Hooks::new(opts, executable_targets, &krate_target_dirs);
+    let mut hooks_checked = Instant::now();
 
     // Track failed targets to detect when all targets fail
     let mut failed_targets = HashSet::new();
 
     loop {
-        Child::refresh(opts, n_children, children.as_mut_slice());
+        if status_refreshed.is_none_or(|refreshed| refreshed.elapsed() >= status_interval) {
+            status = status_table(&krate_target_dirs);
+            status_refreshed = Some(Instant::now());
+            if opts.no_ui {
+                for line in &status {
+                    println!("{line}");
+                }
+                // smoelius: Force the next line of child output to be preceded by a divider.
+                i_instance_prev = instances.len();
+            }
+        }
+
+        if stats_file_written.is_none_or(|written| written.elapsed() >= STATS_FILE_INTERVAL) {
+            write_stats_file(opts, &krate_target_dirs)?;
+            stats_file_written = Some(Instant::now());
+        }
+
+        if Hooks::enabled(opts) && hooks_checked.elapsed() >= HOOKS_INTERVAL {
+            hooks.check()?;
+            hooks_checked = Instant::now();
+        }
+
+        Child::refresh(opts, &status, n_children, children.as_mut_slice());
 
         // If all targets have failed, terminate gracefully
-        if failed_targets.len() == executable_targets.len() {
+        if failed_targets.len() == instances.len() {
             bail!("All targets failed to start");
         }
 
-        if n_children < n_cpus && (i_task < executable_targets.len() || !config.sufficient_cpus) {
-            let Some((executable, target)) = executable_targets_iter.next() else {
-                unreachable!();
-            };
+        let remaining_time =
+            deadline.map(|deadline| deadline.saturating_duration_since(Instant::now()).as_secs());
 
-            let i_target = i_task % executable_targets.len();
+        let next = if n_children >= n_cpus {
+            None
+        } else if config.sufficient_cpus {
+            Some(i_task).filter(|&i_task| i_task < instances.len())
+        } else if remaining_time == Some(0) {
+            None
+        } else {
+            // smoelius: Skip targets that are already running or that have failed.
+            scheduler.next(|i_instance| {
+                children[i_instance].is_none() && !failed_targets.contains(&i_instance)
+            })
+        };
 
-            // Skip targets that have already failed
-            if failed_targets.contains(&i_target) {
-                i_task += 1;
-                continue;
-            }
+        if let Some(i_instance) = next {
+            let (executable, target, instance) = instances[i_instance];
 
-            // smoelius: Here is how I think this condition could arise. Suppose there are three
-            // targets and two cpus, and that tasks 0 and 1 are currently running. Suppose then that
-            // task 1 completes and task 2 cannot be started for some reason, so cargo-test-fuzz
-            // tries to start task 3. Note that tasks 0 and 3 correspond to the same target. So if
-            // task 0 is still running, `children[i_target]` will be `Some(..)`.
-            if children[i_target].is_some() {
-                assert!(!config.sufficient_cpus);
-                i_task += 1;
-                continue;
-            }
-
-            config.first_run = i_task < executable_targets.len();
+            config.first_run = scheduler.first_run(i_instance);
+            config.remaining_time = remaining_time;
 
             // smoelius: If this is not the target's first run, then there must be insufficient
             // cpus.
             assert!(config.first_run || !config.sufficient_cpus);
 
-            let mut command = fuzz_command(opts, &config, executable, target);
+            let mut command = fuzz_command(opts, &config, executable, target, instance);
 
             let exec = format!("{command:?}");
             command.stdout(Stdio::piped());
@@ -1318,12 +1895,13 @@ fn fuzz(opts: &TestFuzz, executable_targets: &[(Executable, String)]) -> Result<
                 .set_nonblocking(true)
                 .with_context(|| "Could not make receiver non-blocking")?;
             poll.registry()
-                .register(&mut receiver, Token(i_target), Interest::READABLE)
+                .register(&mut receiver, Token(i_instance), Interest::READABLE)
                 .with_context(|| "Could not register receiver")?;
-            children[i_target] = Some(Child {
+            children[i_instance] = Some(Child {
                 exec,
-                target: target.clone(),
+                target: instance_label(target, instance, n_jobs),
                 popen,
+                started: Instant::now(),
                 receiver,
                 unprinted_data: Vec::new(),
                 output_buffer: VecDeque::new(),
@@ -1337,21 +1915,35 @@ fn fuzz(opts: &TestFuzz, executable_targets: &[(Executable, String)]) -> Result<
         }
 
         if n_children == 0 {
-            assert!(config.sufficient_cpus);
-            assert!(i_task >= executable_targets.len());
+            assert!(!config.sufficient_cpus || i_task >= instances.len());
+            if !config.sufficient_cpus && scheduler.all_retired() {
+                eprintln!("All targets retired");
+            }
             break;
         }
 
-        poll.poll(&mut events, None)
+        // smoelius: Wake up in time to refresh the status table, to write the `--stats-file`, and
+        // to check for new crashes and hangs, even if no child produces output.
+        let timeout = [
+            status_refreshed.map(|refreshed| status_interval.saturating_sub(refreshed.elapsed())),
+            stats_file_written
+                .filter(|_| opts.stats_file.is_some())
+                .map(|written| STATS_FILE_INTERVAL.saturating_sub(written.elapsed())),
+            Some(HOOKS_INTERVAL.saturating_sub(hooks_checked.elapsed()))
+                .filter(|_| Hooks::enabled(opts)),
+        ]
+        .into_iter()
+        .flatten()
+        .min();
+        poll.poll(&mut events, timeout)
             .with_context(|| "`poll` failed")?;
 
         for event in &events {
-            let Token(i_target) = event.token();
-            let (_, target) = &executable_targets[i_target];
+            let Token(i_instance) = event.token();
             #[allow(clippy::panic)]
-            let child = children[i_target]
+            let child = children[i_instance]
                 .as_mut()
-                .unwrap_or_else(|| panic!("Child for token {i_target} should exist"));
+                .unwrap_or_else(|| panic!("Child for token {i_instance} should exist"));
 
             let s = child.read_lines()?;
             for line in s.lines() {
@@ -1361,18 +1953,18 @@ fn fuzz(opts: &TestFuzz, executable_targets: &[(Executable, String)]) -> Result<
                 if line.contains("+++ Testing aborted programmatically +++") {
                     child.testing_aborted_programmatically = true;
                 }
-                if opts.no_ui && i_target_prev != i_target {
-                    println!("{target} ---");
+                if opts.no_ui && i_instance_prev != i_instance {
+                    println!("{} ---", child.target);
                 }
                 child.print_line(opts, line.to_owned());
-                i_target_prev = i_target;
+                i_instance_prev = i_instance;
             }
 
             if event.is_read_closed() {
                 #[allow(clippy::panic)]
-                let mut child = children[i_target]
+                let mut child = children[i_instance]
                     .take()
-                    .unwrap_or_else(|| panic!("Child for token {i_target} should exist"));
+                    .unwrap_or_else(|| panic!("Child for token {i_instance} should exist"));
                 poll.registry()
                     .deregister(&mut child.receiver)
                     .with_context(|| "Could not deregister receiver")?;
@@ -1386,38 +1978,77 @@ fn fuzz(opts: &TestFuzz, executable_targets: &[(Executable, String)]) -> Result<
                 if !status.success() {
                     eprintln!(
                         "Warning: Command failed for target {}: {:?}\nstdout: ```\n{}\n```",
-                        target,
+                        child.target,
                         child.exec,
                         itertools::join(child.output_buffer.iter(), "\n")
                     );
-                    failed_targets.insert(i_target);
+                    failed_targets.insert(i_instance);
                     continue;
                 }
 
                 if !child.testing_aborted_programmatically {
                     eprintln!(
                         r#"Warning: Could not find "Testing aborted programmatically" in command output for target {}: {:?}"#,
-                        target, child.exec
+                        child.target, child.exec
                     );
-                    failed_targets.insert(i_target);
+                    failed_targets.insert(i_instance);
                     continue;
                 }
 
                 if opts.exit_code && !child.time_limit_was_reached {
+                    finish(opts, &krate_target_dirs, &mut hooks)?;
                     exit(1);
                 }
+
+                if !config.sufficient_cpus {
+                    let (_, _, output_dir) = &krate_target_dirs[i_instance / n_jobs];
+                    let fuzzer_stats = fuzzer_stats(output_dir);
+                    let summary = scheduler.record_slice(
+                        i_instance,
+                        &fuzzer_stats,
+                        child.started.elapsed().as_secs(),
+                    );
+                    debug!(
+                        "{}: {} new finds in last slice; {} seconds since last find",
+                        child.target, summary.n_new_finds, summary.secs_since_last_find
+                    );
+                    if summary.retired {
+                        eprintln!(
+                            "Retiring target {}: no new finds in {} seconds of fuzzing",
+                            child.target, summary.secs_since_last_find
+                        );
+                    }
+                }
             }
         }
     }
 
+    finish(opts, &krate_target_dirs, &mut hooks)
+}
+
+/// Writes a final `--stats-file` snapshot and runs hooks for any remaining crashes and hangs.
+fn finish(opts: &TestFuzz, krate_target_dirs: &[KrateTargetDir], hooks: &mut Hooks) -> Result<()> {
+    write_stats_file(opts, krate_target_dirs)?;
+    if Hooks::enabled(opts) {
+        hooks.check()?;
+        hooks.wait()?;
+    }
     Ok(())
 }
 
+fn write_stats_file(opts: &TestFuzz, krate_target_dirs: &[KrateTargetDir]) -> Result<()> {
+    let Some(path) = &opts.stats_file else {
+        return Ok(());
+    };
+    append_snapshot(Path::new(path), krate_target_dirs)
+}
+
 fn fuzz_command(
     opts: &TestFuzz,
     config: &Config,
     executable: &Executable,
     target: &str,
+    instance: usize,
 ) -> Command {
     let input_dir = if opts.resume || !config.first_run {
         "-".to_owned()
@@ -1448,14 +2079,17 @@ fn fuzz_command(
         &input_dir,
         "-o",
         &output_dir.to_string_lossy(),
-        "-M",
-        "default",
+        if instance == 0 { "-M" } else { "-S" },
+        &instance_name(instance),
     ]
     .into_iter()
     .map(String::from)
     .collect::<Vec<_>>();
     if !config.sufficient_cpus {
-        args.extend(["-V".to_owned(), opts.slice.to_string()]);
+        let slice = config
+            .remaining_time
+            .map_or(opts.slice, |remaining_time| remaining_time.min(opts.slice));
+        args.extend(["-V".to_owned(), slice.to_string()]);
     } else if let Some(max_total_time) = opts.max_total_time {
         args.extend(["-V".to_owned(), max_total_time.to_string()]);
     }
@@ -1480,6 +2114,22 @@ fn fuzz_command(
     command
 }
 
+fn instance_name(instance: usize) -> String {
+    if instance == 0 {
+        MAIN_INSTANCE.to_owned()
+    } else {
+        format!("secondary{instance}")
+    }
+}
+
+fn instance_label(target: &str, instance: usize, n_jobs: usize) -> String {
+    if n_jobs == 1 {
+        target.to_owned()
+    } else {
+        format!("{target} ({})", instance_name(instance))
+    }
+}
+
 fn auto_generate_corpora(
     opts: &TestFuzz,
     executable_targets: &[(Executable, String)],
@@ -0,0 +1,252 @@
+use crate::{
+    KrateTargetDir,
+    fuzzer_stats::{FuzzerStats, instance_fuzzer_stats},
+};
+use anyhow::{Context, Result};
+use log::debug;
+use serde_json::{Map, Number, Value, json};
+use std::{
+    fmt::Write as _,
+    fs::OpenOptions,
+    io::{BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream},
+    path::Path,
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// How long to wait on a metrics client before giving up on the connection
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+type Metric = (
+    &'static str,
+    &'static str,
+    &'static str,
+    fn(&FuzzerStats) -> Option<f64>,
+);
+
+/// Metrics served in Prometheus text format: name, type, help, and accessor
+#[allow(clippy::cast_precision_loss)]
+const METRICS: &[Metric] = &[
+    (
+        "test_fuzz_execs_done",
+        "counter",
+        "Total number of executions",
+        |fuzzer_stats| fuzzer_stats.execs_done().map(|n| n as f64),
+    ),
+    (
+        "test_fuzz_execs_per_sec",
+        "gauge",
+        "Executions per second",
+        FuzzerStats::execs_per_sec,
+    ),
+    (
+        "test_fuzz_corpus_count",
+        "gauge",
+        "Number of entries in the queue",
+        |fuzzer_stats| fuzzer_stats.corpus_count().map(|n| n as f64),
+    ),
+    (
+        "test_fuzz_bitmap_cvg",
+        "gauge",
+        "Percentage of the coverage bitmap that is set",
+        FuzzerStats::bitmap_cvg,
+    ),
+    (
+        "test_fuzz_saved_crashes",
+        "gauge",
+        "Number of unique crashes saved",
+        |fuzzer_stats| fuzzer_stats.saved_crashes().map(|n| n as f64),
+    ),
+    (
+        "test_fuzz_saved_hangs",
+        "gauge",
+        "Number of unique hangs saved",
+        |fuzzer_stats| fuzzer_stats.saved_hangs().map(|n| n as f64),
+    ),
+    (
+        "test_fuzz_last_find_seconds",
+        "gauge",
+        "Unix time of the last new find, or zero if there has been none",
+        |fuzzer_stats| fuzzer_stats.last_find().map(|n| n as f64),
+    ),
+];
+
+/// Appends one JSON line per target to `path`. Each line holds the time, the crate and target
+/// names, and each fuzzer instance's parsed `fuzzer_stats`.
+pub fn append_snapshot(path: &Path, krate_target_dirs: &[KrateTargetDir]) -> Result<()> {
+    let time = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default();
+
+    let mut lines = String::new();
+    for (krate, target, output_dir) in krate_target_dirs {
+        let instances = instance_fuzzer_stats(output_dir)
+            .into_iter()
+            .map(|(instance, fuzzer_stats)| (instance, fields_to_json(&fuzzer_stats)))
+            .collect::<Map<_, _>>();
+        let snapshot = json!({
+            "time": time,
+            "crate": krate,
+            "target": target,
+            "instances": instances,
+        });
+        lines.push_str(&snapshot.to_string());
+        lines.push('\n');
+    }
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("`open` failed for `{}`", path.to_string_lossy()))?;
+    file.write_all(lines.as_bytes())
+        .with_context(|| format!("`write_all` failed for `{}`", path.to_string_lossy()))
+}
+
+/// Converts `fuzzer_stats` to a JSON object, representing numeric values as numbers.
+fn fields_to_json(fuzzer_stats: &FuzzerStats) -> Value {
+    fuzzer_stats
+        .fields()
+        .map(|(key, value)| {
+            let value = value
+                .parse::<u64>()
+                .map(Value::from)
+                .ok()
+                .or_else(|| {
+                    value
+                        .parse::<f64>()
+                        .ok()
+                        .and_then(Number::from_f64)
+                        .map(Value::Number)
+                })
+                .unwrap_or_else(|| Value::from(value));
+            (key.to_owned(), value)
+        })
+        .collect::<Map<_, _>>()
+        .into()
+}
+
+/// Formats the targets' `fuzzer_stats` in Prometheus text format, with one sample per fuzzer
+/// instance per metric.
+pub fn prometheus_text(krate_target_dirs: &[KrateTargetDir]) -> String {
+    let instance_fuzzer_stats = krate_target_dirs
+        .iter()
+        .map(|(krate, target, output_dir)| (krate, target, instance_fuzzer_stats(output_dir)))
+        .collect::<Vec<_>>();
+
+    let mut text = String::new();
+    for &(name, kind, help, accessor) in METRICS {
+        let _ = writeln!(text, "# HELP {name} {help}");
+        let _ = writeln!(text, "# TYPE {name} {kind}");
+        for (krate, target, instances) in &instance_fuzzer_stats {
+            for (instance, fuzzer_stats) in instances {
+                let Some(value) = accessor(fuzzer_stats) else {
+                    continue;
+                };
+                let _ = writeln!(
+                    text,
+                    "{name}{{crate=\"{}\",target=\"{}\",instance=\"{}\"}} {value}",
+                    escape_label_value(krate),
+                    escape_label_value(target),
+                    escape_label_value(instance),
+                );
+            }
+        }
+    }
+    text
+}
+
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', r"\\")
+        .replace('"', "\\\"")
+        .replace('\n', r"\n")
+}
+
+/// Serves [`prometheus_text`] over HTTP at `addr` from a background thread.
+///
+/// Every request receives the metrics, regardless of its method or path.
+pub fn serve_metrics(addr: &str, krate_target_dirs: Vec<KrateTargetDir>) -> Result<()> {
+    let listener =
+        TcpListener::bind(addr).with_context(|| format!("Could not bind to `{addr}`"))?;
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let result = stream
+                .map_err(Into::into)
+                .and_then(|stream| respond(stream, &krate_target_dirs));
+            if let Err(error) = result {
+                debug!("Could not serve metrics: {error:?}");
+            }
+        }
+    });
+    Ok(())
+}
+
+fn respond(mut stream: TcpStream, krate_target_dirs: &[KrateTargetDir]) -> Result<()> {
+    // smoelius: Requests are served one at a time. So a client that stalls while sending its
+    // request or receiving the response must not be allowed to block the others.
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    stream.set_write_timeout(Some(TIMEOUT))?;
+
+    // smoelius: Consume the request headers so that the client does not see a reset connection.
+    let mut reader = BufReader::new(&stream);
+    let mut line = String::new();
+    while reader.read_line(&mut line)? > 0 && line != "\r\n" && line != "\n" {
+        line.clear();
+    }
+
+    let body = prometheus_text(krate_target_dirs);
+    write!(
+        stream,
+        "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: \
+         {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )?;
+    stream.flush().map_err(Into::into)
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    use super::{FuzzerStats, TIMEOUT, escape_label_value, fields_to_json, respond};
+    use serde_json::json;
+    use std::{
+        net::{TcpListener, TcpStream},
+        time::Instant,
+    };
+
+    #[test]
+    fn json_fields() {
+        let fuzzer_stats = FuzzerStats::parse(
+            "corpus_count : 42\nexecs_per_sec : 12.5\nbitmap_cvg : 3.21%\ntarget_mode : default\n",
+        );
+        assert_eq!(
+            json!({
+                "bitmap_cvg": "3.21%",
+                "corpus_count": 42,
+                "execs_per_sec": 12.5,
+                "target_mode": "default",
+            }),
+            fields_to_json(&fuzzer_stats)
+        );
+    }
+
+    #[test]
+    fn label_values() {
+        assert_eq!(r#"a\"b\\c\nd"#, escape_label_value("a\"b\\c\nd"));
+    }
+
+    #[test]
+    fn stalled_client_times_out() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        // smoelius: The client connects but never sends a request.
+        let _client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (stream, _) = listener.accept().unwrap();
+        let start = Instant::now();
+        assert!(respond(stream, &[]).is_err());
+        assert!(start.elapsed() < 2 * TIMEOUT);
+    }
+}
@@ -0,0 +1,202 @@
+use crate::fuzzer_stats::FuzzerStats;
+
+/// Decides which target to fuzz next when there are fewer cpus than targets.
+///
+/// Each target that has not yet been fuzzed is scheduled first, in order. After that, a target's
+/// share of slices is proportional to one plus the number of new queue entries it found in its
+/// most recent slice. If `retire_after` is set, a target that goes that many seconds of fuzzing
+/// without a new find is retired and never scheduled again.
+pub struct Scheduler {
+    targets: Vec<TargetState>,
+    retire_after: Option<u64>,
+}
+
+#[derive(Default)]
+struct TargetState {
+    n_slices: u64,
+    corpus_count: Option<u64>,
+    n_new_finds: u64,
+    secs_since_last_find: u64,
+    retired: bool,
+}
+
+/// What a target's most recent slice revealed about it
+#[derive(Debug, Eq, PartialEq)]
+pub struct SliceSummary {
+    pub n_new_finds: u64,
+    pub secs_since_last_find: u64,
+    pub retired: bool,
+}
+
+impl Scheduler {
+    pub fn new(n_targets: usize, retire_after: Option<u64>) -> Self {
+        Self {
+            targets: (0..n_targets).map(|_| TargetState::default()).collect(),
+            retire_after,
+        }
+    }
+
+    /// Returns the target that should be fuzzed next, considering only those for which
+    /// `available` returns true, or `None` if there is no such target.
+    pub fn next(&self, available: impl Fn(usize) -> bool) -> Option<usize> {
+        let candidates = (0..self.targets.len())
+            .filter(|&i| !self.targets[i].retired && available(i))
+            .collect::<Vec<_>>();
+
+        if let Some(&i) = candidates.iter().find(|&&i| self.targets[i].n_slices == 0) {
+            return Some(i);
+        }
+
+        // smoelius: Compare `n_slices / (1 + n_new_finds)` by cross-multiplying. Ties go to the
+        // lower index, i.e., `min_by` returns the first minimal element.
+        candidates.into_iter().min_by(|&i, &j| {
+            let x = &self.targets[i];
+            let y = &self.targets[j];
+            let lhs = u128::from(x.n_slices) * (1 + u128::from(y.n_new_finds));
+            let rhs = u128::from(y.n_slices) * (1 + u128::from(x.n_new_finds));
+            lhs.cmp(&rhs)
+        })
+    }
+
+    /// Returns true if `target` has not yet been fuzzed.
+    pub fn first_run(&self, target: usize) -> bool {
+        self.targets[target].n_slices == 0
+    }
+
+    /// Returns true if every target has been retired.
+    pub fn all_retired(&self) -> bool {
+        self.targets.iter().all(|state| state.retired)
+    }
+
+    /// Records that a slice of `target` lasting `elapsed` seconds completed. `fuzzer_stats`
+    /// are those of the target's instances.
+    pub fn record_slice(
+        &mut self,
+        target: usize,
+        fuzzer_stats: &[FuzzerStats],
+        elapsed: u64,
+    ) -> SliceSummary {
+        let retire_after = self.retire_after;
+        let state = &mut self.targets[target];
+
+        let corpus_count = fuzzer_stats
+            .iter()
+            .filter_map(FuzzerStats::corpus_count)
+            .sum::<u64>();
+        // smoelius: On a target's first slice, there is no previous count to compare against. So
+        // count the entries the instances found themselves, excluding the initial corpus.
+        let n_new_finds = state.corpus_count.map_or_else(
+            || {
+                fuzzer_stats
+                    .iter()
+                    .filter_map(FuzzerStats::corpus_found)
+                    .sum()
+            },
+            |prev| corpus_count.saturating_sub(prev),
+        );
+        let secs_since_last_find = if n_new_finds > 0 {
+            fuzzer_stats
+                .iter()
+                .filter_map(|instance| {
+                    let last_find = instance.last_find().filter(|&last_find| last_find != 0)?;
+                    let last_update = instance.last_update()?;
+                    Some(last_update.saturating_sub(last_find))
+                })
+                .min()
+                .unwrap_or_default()
+        } else {
+            state.secs_since_last_find + elapsed
+        };
+
+        state.n_slices += 1;
+        state.corpus_count = Some(corpus_count);
+        state.n_new_finds = n_new_finds;
+        state.secs_since_last_find = secs_since_last_find;
+        state.retired = retire_after.is_some_and(|secs| secs_since_last_find >= secs);
+
+        SliceSummary {
+            n_new_finds,
+            secs_since_last_find,
+            retired: state.retired,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FuzzerStats, Scheduler, SliceSummary};
+
+    fn stats(corpus_count: u64, corpus_found: u64, last_find: u64, run_time: u64) -> FuzzerStats {
+        FuzzerStats::parse(&format!(
+            "last_update : {}\ncorpus_count : {corpus_count}\ncorpus_found : \
+             {corpus_found}\nlast_find : {last_find}\n",
+            1_000 + run_time
+        ))
+    }
+
+    #[test]
+    fn unfuzzed_targets_first() {
+        let mut scheduler = Scheduler::new(3, Some(100));
+        assert_eq!(Some(0), scheduler.next(|_| true));
+        scheduler.record_slice(0, &[stats(10, 5, 1_010, 20)], 20);
+        assert_eq!(Some(1), scheduler.next(|_| true));
+        assert_eq!(Some(2), scheduler.next(|i| i != 1));
+    }
+
+    #[test]
+    fn productive_targets_favored() {
+        let mut scheduler = Scheduler::new(2, Some(100));
+        scheduler.record_slice(0, &[stats(10, 5, 1_010, 20)], 20);
+        scheduler.record_slice(1, &[stats(10, 0, 0, 20)], 20);
+        assert_eq!(Some(0), scheduler.next(|_| true));
+        scheduler.record_slice(0, &[stats(20, 10, 1_015, 20)], 20);
+        // smoelius: Target 0 has had two slices, but found ten new entries in its last one.
+        assert_eq!(Some(0), scheduler.next(|_| true));
+    }
+
+    #[test]
+    fn saturated_targets_retired() {
+        let mut scheduler = Scheduler::new(2, Some(50));
+        assert_eq!(
+            SliceSummary {
+                n_new_finds: 5,
+                secs_since_last_find: 10,
+                retired: false,
+            },
+            scheduler.record_slice(0, &[stats(10, 5, 1_010, 20)], 20)
+        );
+        assert_eq!(
+            SliceSummary {
+                n_new_finds: 0,
+                secs_since_last_find: 30,
+                retired: false,
+            },
+            scheduler.record_slice(0, &[stats(10, 0, 0, 20)], 20)
+        );
+        assert_eq!(
+            SliceSummary {
+                n_new_finds: 0,
+                secs_since_last_find: 50,
+                retired: true,
+            },
+            scheduler.record_slice(0, &[stats(10, 0, 0, 20)], 20)
+        );
+        assert_eq!(Some(1), scheduler.next(|_| true));
+        assert!(!scheduler.all_retired());
+    }
+
+    #[test]
+    fn targets_not_retired_by_default() {
+        let mut scheduler = Scheduler::new(1, None);
+        scheduler.record_slice(0, &[stats(10, 5, 1_010, 20)], 20);
+        for _ in 0..10 {
+            assert!(
+                !scheduler
+                    .record_slice(0, &[stats(10, 0, 0, 20)], 20)
+                    .retired
+            );
+        }
+        assert_eq!(Some(0), scheduler.next(|_| true));
+        assert!(!scheduler.all_retired());
+    }
+}
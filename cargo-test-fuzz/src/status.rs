@@ -0,0 +1,180 @@
+use crate::{
+    KrateTargetDir,
+    fuzzer_stats::{FuzzerStats, fuzzer_stats},
+};
+use std::{
+    fmt::Write,
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+const HEADER: [&str; 7] = [
+    "TARGET",
+    "EXECS/SEC",
+    "CORPUS",
+    "COVERAGE",
+    "CRASHES",
+    "HANGS",
+    "LAST NEW FIND",
+];
+
+/// A summary of one target's fuzzing progress, aggregated over all of its fuzzer instances
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TargetStatus {
+    pub execs_per_sec: f64,
+    pub corpus_count: u64,
+    pub coverage: f64,
+    pub crashes: u64,
+    pub hangs: u64,
+    /// Unix time of the target's last new find, or `None` if it has found nothing
+    pub last_find: Option<u64>,
+}
+
+impl TargetStatus {
+    /// Returns `None` if no instance of the target has written a `fuzzer_stats` file.
+    ///
+    /// Instances share their finds, so corpus size and coverage are the maximum over instances,
+    /// while execution speed, crashes, and hangs are summed.
+    pub fn from_fuzzer_stats(fuzzer_stats: &[FuzzerStats]) -> Option<Self> {
+        if fuzzer_stats.is_empty() {
+            return None;
+        }
+        let max_f64 = |f: fn(&FuzzerStats) -> Option<f64>| {
+            fuzzer_stats.iter().filter_map(f).fold(0.0, f64::max)
+        };
+        Some(Self {
+            execs_per_sec: fuzzer_stats
+                .iter()
+                .filter_map(FuzzerStats::execs_per_sec)
+                .sum(),
+            corpus_count: fuzzer_stats
+                .iter()
+                .filter_map(FuzzerStats::corpus_count)
+                .max()
+                .unwrap_or_default(),
+            coverage: max_f64(FuzzerStats::bitmap_cvg),
+            crashes: fuzzer_stats
+                .iter()
+                .filter_map(FuzzerStats::saved_crashes)
+                .sum(),
+            hangs: fuzzer_stats
+                .iter()
+                .filter_map(FuzzerStats::saved_hangs)
+                .sum(),
+            last_find: fuzzer_stats
+                .iter()
+                .filter_map(FuzzerStats::last_find)
+                .filter(|&last_find| last_find != 0)
+                .max(),
+        })
+    }
+
+    /// `output_dir` is the target's output directory.
+    pub fn from_output_dir(output_dir: &Path) -> Option<Self> {
+        Self::from_fuzzer_stats(&fuzzer_stats(output_dir))
+    }
+}
+
+/// Formats a table with one row per target.
+pub fn status_table(krate_target_dirs: &[KrateTargetDir]) -> Vec<String> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default();
+
+    let mut rows = vec![HEADER.map(ToOwned::to_owned).to_vec()];
+    for (_, target, output_dir) in krate_target_dirs {
+        let row = TargetStatus::from_output_dir(output_dir).map_or_else(
+            || {
+                let mut row = vec![target.to_owned()];
+                row.extend(["-"; 5].map(ToOwned::to_owned));
+                row.push("not started".to_owned());
+                row
+            },
+            |status| {
+                vec![
+                    target.to_owned(),
+                    format!("{:.0}", status.execs_per_sec),
+                    status.corpus_count.to_string(),
+                    format!("{:.2}%", status.coverage),
+                    status.crashes.to_string(),
+                    status.hangs.to_string(),
+                    status
+                        .last_find
+                        .map_or_else(|| "never".to_owned(), |last_find| ago(now, last_find)),
+                ]
+            },
+        );
+        rows.push(row);
+    }
+
+    let widths = (0..HEADER.len())
+        .map(|i| {
+            rows.iter()
+                .map(|row| row[i].len())
+                .max()
+                .unwrap_or_default()
+        })
+        .collect::<Vec<_>>();
+
+    rows.into_iter()
+        .map(|row| {
+            // smoelius: Left-align the target name and right-align the numbers.
+            let mut line = format!("{:<width$}", row[0], width = widths[0]);
+            for (cell, width) in row.iter().zip(&widths).skip(1) {
+                let _ = write!(line, "  {cell:>width$}");
+            }
+            line
+        })
+        .collect()
+}
+
+fn ago(now: u64, then: u64) -> String {
+    let secs = now.saturating_sub(then);
+    if secs < 60 {
+        format!("{secs}s ago")
+    } else if secs < 60 * 60 {
+        format!("{}m ago", secs / 60)
+    } else if secs < 24 * 60 * 60 {
+        format!("{}h ago", secs / (60 * 60))
+    } else {
+        format!("{}d ago", secs / (24 * 60 * 60))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FuzzerStats, TargetStatus, ago};
+
+    #[test]
+    fn aggregate_instances() {
+        let main = FuzzerStats::parse(
+            "execs_per_sec : 100.5\ncorpus_count : 10\nbitmap_cvg : 1.50%\nsaved_crashes : \
+             1\nsaved_hangs : 0\nlast_find : 1000\n",
+        );
+        let secondary = FuzzerStats::parse(
+            "execs_per_sec : 200.5\ncorpus_count : 12\nbitmap_cvg : 1.25%\nsaved_crashes : \
+             2\nsaved_hangs : 3\nlast_find : 0\n",
+        );
+        assert_eq!(
+            Some(TargetStatus {
+                execs_per_sec: 301.0,
+                corpus_count: 12,
+                coverage: 1.5,
+                crashes: 3,
+                hangs: 3,
+                last_find: Some(1000),
+            }),
+            TargetStatus::from_fuzzer_stats(&[main, secondary])
+        );
+        assert_eq!(None, TargetStatus::from_fuzzer_stats(&[]));
+    }
+
+    #[test]
+    fn durations() {
+        assert_eq!("5s ago", ago(105, 100));
+        assert_eq!("2m ago", ago(220, 100));
+        assert_eq!("3h ago", ago(3 * 60 * 60 + 100, 100));
+        assert_eq!("1d ago", ago(24 * 60 * 60 + 100, 100));
+    }
+}
@@ -0,0 +1,127 @@
+use std::{
+    fs::{copy, create_dir, read_to_string, write},
+    path::Path,
+    process::Command,
+};
+use tempfile::tempdir;
+use testing::LoggedAssert;
+
+#[cfg_attr(dylint_lib = "general", allow(abs_home_path))]
+const ROOT: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/..");
+
+const LIB_RS: &str = r#"use serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+
+#[test_fuzz::test_fuzz(only_generic_args)]
+fn target<T: Clone + Debug + Serialize>(x: T) {}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct Struct;
+
+#[test_fuzz::test_fuzz_impl]
+impl Struct {
+    #[test_fuzz::test_fuzz(rename = "renamed", only_generic_args)]
+    fn method<T: Clone + Debug + Serialize>(&self, x: T) {}
+}
+
+#[test]
+fn test() {
+    target(0u8);
+    target(String::new());
+    Struct.method(0u8);
+}
+"#;
+
+#[cfg_attr(dylint_lib = "general", allow(non_thread_safe_call_in_test))]
+#[test]
+fn apply_generic_args() {
+    let tempdir = tempdir().unwrap();
+    let package = tempdir.path();
+
+    write(
+        package.join("Cargo.toml"),
+        format!(
+            r#"[package]
+name = "apply"
+version = "0.1.0"
+edition = "2024"
+publish = false
+
+[dependencies]
+serde = {{ version = "1.0", features = ["derive"] }}
+test-fuzz = {{ path = "{ROOT}/test-fuzz" }}
+
+[workspace]
+"#
+        ),
+    )
+    .unwrap();
+    // smoelius: Reuse the repository's lockfile so that the same dependency versions are used.
+    copy(
+        Path::new(ROOT).join("Cargo.lock"),
+        package.join("Cargo.lock"),
+    )
+    .unwrap();
+    create_dir(package.join("src")).unwrap();
+    write(package.join("src/lib.rs"), LIB_RS).unwrap();
+
+    cargo_test(package).logged_assert().success();
+
+    test_fuzz(package, &["--suggest-generic-args", "--apply-generic-args"])
+        .logged_assert()
+        .success();
+
+    let lib_rs = read_to_string(package.join("src/lib.rs")).unwrap();
+    assert!(
+        lib_rs.contains(r#"#[test_fuzz::test_fuzz(generic_args = ["std::string::String", "u8"])]"#),
+        "{lib_rs}"
+    );
+    assert!(
+        lib_rs.contains(r#"#[test_fuzz::test_fuzz(rename = "renamed", generic_args = "u8")]"#),
+        "{lib_rs}"
+    );
+
+    // smoelius: The rewritten attributes generate one target per instantiation.
+    let assert = test_fuzz(package, &["--list"]).logged_assert().success();
+    let stdout = std::str::from_utf8(&assert.get_output().stdout).unwrap();
+    assert!(stdout.contains("\"target_u8\""), "{stdout}");
+    assert!(stdout.contains("\"renamed\""), "{stdout}");
+}
+
+fn cargo_test(package: &Path) -> Command {
+    let serde_format_feature = "test-fuzz/".to_owned() + internal::serde_format::as_feature();
+    #[allow(clippy::disallowed_methods, reason = "runs `cargo test`")]
+    let mut command = Command::new("cargo");
+    command.args([
+        "test",
+        "--manifest-path",
+        &package.join("Cargo.toml").to_string_lossy(),
+        "--features",
+        &serde_format_feature,
+    ]);
+    command
+}
+
+fn test_fuzz(package: &Path, args: &[&str]) -> Command {
+    let serde_format_feature = "test-fuzz/".to_owned() + internal::serde_format::as_feature();
+    #[allow(clippy::disallowed_methods, reason = "runs `cargo test-fuzz`")]
+    let mut command = Command::new("cargo");
+    // smoelius: `cargo-test-fuzz` looks for recorded generic args in the target directory of the
+    // current directory's package.
+    command
+        .current_dir(package)
+        .args([
+            "run",
+            "--bin=cargo-test-fuzz",
+            "--manifest-path",
+            &(ROOT.to_owned() + "/Cargo.toml"),
+            "--",
+            "test-fuzz",
+            "--manifest-path",
+            &package.join("Cargo.toml").to_string_lossy(),
+            "--features",
+            &serde_format_feature,
+        ])
+        .args(args);
+    command
+}
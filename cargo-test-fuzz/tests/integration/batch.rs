@@ -0,0 +1,30 @@
+use internal::dirs::corpus_directory_from_target;
+use std::fs::{read_dir, remove_dir_all};
+use testing::{LoggedAssert, fuzzable};
+
+#[cfg_attr(dylint_lib = "general", allow(non_thread_safe_call_in_test))]
+#[test]
+fn batch() {
+    let corpus = corpus_directory_from_target("mixed", "pair");
+    remove_dir_all(&corpus).unwrap_or_default();
+
+    fuzzable::test("mixed", "test_pair")
+        .unwrap()
+        .logged_assert()
+        .success();
+
+    assert_eq!(2, read_dir(&corpus).unwrap().count());
+
+    // smoelius: The file that panics is rerun in its own process. So the results should be the
+    // same with and without `--batch`.
+    for args in [&["--replay=corpus"][..], &["--replay=corpus", "--batch"]] {
+        let assert = fuzzable::test_fuzz("mixed", "pair")
+            .unwrap()
+            .args(args)
+            .logged_assert()
+            .success();
+        let stdout = std::str::from_utf8(&assert.get_output().stdout).unwrap();
+        assert_eq!(1, stdout.matches(": Ret(())\n").count(), "{stdout}");
+        assert_eq!(1, stdout.matches("x is true").count(), "{stdout}");
+    }
+}
@@ -0,0 +1,23 @@
+use internal::dirs::corpus_directory_from_target;
+use predicates::prelude::*;
+use std::fs::remove_dir_all;
+use testing::{LoggedAssert, fuzzable};
+
+#[cfg_attr(dylint_lib = "general", allow(non_thread_safe_call_in_test))]
+#[test]
+fn capture_globals() {
+    let corpus = corpus_directory_from_target("capture_globals", "target");
+    remove_dir_all(corpus).unwrap_or_default();
+
+    fuzzable::test("capture_globals", "test")
+        .unwrap()
+        .logged_assert()
+        .success();
+
+    fuzzable::test_fuzz("capture_globals", "target")
+        .unwrap()
+        .args(["--replay=corpus"])
+        .logged_assert()
+        .success()
+        .stdout(predicate::str::contains("Ret(123)"));
+}
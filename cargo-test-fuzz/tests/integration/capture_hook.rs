@@ -0,0 +1,27 @@
+use internal::dirs::corpus_directory_from_target;
+use predicates::prelude::*;
+use std::fs::{read_dir, remove_dir_all};
+use testing::{LoggedAssert, fuzzable};
+
+#[cfg_attr(dylint_lib = "general", allow(non_thread_safe_call_in_test))]
+#[test]
+fn capture_hook() {
+    let corpus = corpus_directory_from_target("test_fuzz_fuzzable", "library_target");
+    remove_dir_all(&corpus).unwrap_or_default();
+
+    // smoelius: `library_target` is not compiled with `cfg(test)` when called from an integration
+    // test. So its calls are captured only because of the integration test's `capture_hook!`.
+    fuzzable::test("capture_hook", "test")
+        .unwrap()
+        .logged_assert()
+        .success();
+
+    assert_eq!(1, read_dir(&corpus).unwrap().count());
+
+    fuzzable::test_fuzz_all()
+        .unwrap()
+        .args(["--exact", "library_target", "--display=corpus"])
+        .logged_assert()
+        .success()
+        .stdout(predicate::str::contains("Args { x: 1 }"));
+}
@@ -0,0 +1,141 @@
+use predicates::prelude::*;
+use std::{
+    fs::{copy, create_dir, write},
+    path::Path,
+    process::Command,
+};
+use tempfile::tempdir;
+use testing::LoggedAssert;
+
+#[cfg_attr(dylint_lib = "general", allow(abs_home_path))]
+const ROOT: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/..");
+
+const LIB_RS: &str = "\
+#[path = \"sp\u{e9}cial.rs\"]
+mod special;
+
+#[test_fuzz::test_fuzz]
+fn calls_leaf(x: u8) {
+    middle(x);
+}
+
+fn middle(x: u8) -> bool {
+    x != special::leaf()
+}
+
+#[test_fuzz::test_fuzz]
+fn unaffected(x: u8) {
+    let _ = x.checked_add(1);
+}
+
+#[test]
+fn test() {
+    calls_leaf(0);
+    unaffected(0);
+}
+";
+
+#[cfg_attr(dylint_lib = "general", allow(non_thread_safe_call_in_test))]
+#[test]
+fn changed_since() {
+    let tempdir = tempdir().unwrap();
+    let package = tempdir.path();
+
+    write(
+        package.join("Cargo.toml"),
+        format!(
+            r#"[package]
+name = "changed"
+version = "0.1.0"
+edition = "2024"
+publish = false
+
+[dependencies]
+serde = "1.0"
+test-fuzz = {{ path = "{ROOT}/test-fuzz" }}
+
+[workspace]
+"#
+        ),
+    )
+    .unwrap();
+    // smoelius: Reuse the repository's lockfile so that the same dependency versions are used.
+    copy(
+        Path::new(ROOT).join("Cargo.lock"),
+        package.join("Cargo.lock"),
+    )
+    .unwrap();
+    create_dir(package.join("src")).unwrap();
+    write(package.join("src/lib.rs"), LIB_RS).unwrap();
+    // smoelius: git quotes paths with non-ASCII characters in its diff output.
+    write_leaf(package, 1);
+
+    git(package, &["init", "--quiet"]);
+    git(package, &["add", "."]);
+    git(
+        package,
+        &["commit", "--quiet", "--message", "Initial commit"],
+    );
+
+    // smoelius: `changed_since` asks each target's `entry` test for its location, i.e., it sets
+    // `TEST_FUZZ_LOCATION`.
+    test_fuzz(package)
+        .logged_assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "No fuzz targets are affected by the changes since `HEAD`",
+        ));
+
+    write_leaf(package, 2);
+
+    let assert = test_fuzz(package).logged_assert().success();
+    let stdout = std::str::from_utf8(&assert.get_output().stdout).unwrap();
+    assert!(stdout.contains("\"calls_leaf\""), "{stdout}");
+    assert!(!stdout.contains("\"unaffected\""), "{stdout}");
+}
+
+fn write_leaf(package: &Path, value: u8) {
+    write(
+        package.join("src/sp\u{e9}cial.rs"),
+        format!("pub fn leaf() -> u8 {{\n    {value}\n}}\n"),
+    )
+    .unwrap();
+}
+
+fn git(dir: &Path, args: &[&str]) {
+    #[allow(clippy::disallowed_methods, reason = "runs `git`")]
+    let mut command = Command::new("git");
+    command
+        .current_dir(dir)
+        .args([
+            "-c",
+            "user.name=test-fuzz",
+            "-c",
+            "user.email=test-fuzz@example.com",
+        ])
+        .args(args)
+        .logged_assert()
+        .success();
+}
+
+fn test_fuzz(package: &Path) -> Command {
+    let serde_format_feature = "test-fuzz/".to_owned() + internal::serde_format::as_feature();
+    #[allow(clippy::disallowed_methods, reason = "runs `cargo test-fuzz`")]
+    let mut command = Command::new("cargo");
+    command.args([
+        "run",
+        "--bin=cargo-test-fuzz",
+        "--manifest-path",
+        &(ROOT.to_owned() + "/Cargo.toml"),
+        "--",
+        "test-fuzz",
+        "--manifest-path",
+        &package.join("Cargo.toml").to_string_lossy(),
+        "--features",
+        &serde_format_feature,
+        "--changed-since",
+        "HEAD",
+        "--list",
+    ]);
+    command
+}
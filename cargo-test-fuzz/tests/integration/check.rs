@@ -0,0 +1,37 @@
+use internal::dirs::corpus_directory_from_target;
+use predicates::prelude::*;
+use std::fs::remove_dir_all;
+use testing::{LoggedAssert, fuzzable};
+
+#[cfg_attr(dylint_lib = "general", allow(non_thread_safe_call_in_test))]
+#[test]
+fn check() {
+    let corpus = corpus_directory_from_target("check", "target");
+    remove_dir_all(corpus).unwrap_or_default();
+
+    fuzzable::test("check", "test")
+        .unwrap()
+        .logged_assert()
+        .success();
+
+    fuzzable::test_fuzz("check", "target")
+        .unwrap()
+        .args(["--replay=corpus"])
+        .logged_assert()
+        .success()
+        .stdout(predicate::str::contains("Ret(6)"));
+
+    fuzzable::test("check", "test_violation")
+        .unwrap()
+        .logged_assert()
+        .success();
+
+    fuzzable::test_fuzz("check", "target")
+        .unwrap()
+        .args(["--replay=corpus"])
+        .logged_assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "`halves_to_arg` does not hold for Args { x: 200 }",
+        ));
+}
@@ -0,0 +1,70 @@
+use internal::dirs::{corpus_directory_from_target, ret_directory_from_target};
+use predicates::prelude::*;
+use std::fs::{read_dir, read_to_string, remove_dir_all, write};
+use tempfile::tempdir;
+use testing::{LoggedAssert, fuzzable};
+
+#[cfg_attr(dylint_lib = "general", allow(non_thread_safe_call_in_test))]
+#[test]
+fn check_regressions() {
+    let corpus = corpus_directory_from_target("capture_ret", "target");
+    let ret = ret_directory_from_target("capture_ret", "target");
+    remove_dir_all(&corpus).unwrap_or_default();
+    remove_dir_all(&ret).unwrap_or_default();
+
+    fuzzable::test("capture_ret", "test")
+        .unwrap()
+        .logged_assert()
+        .success();
+
+    let entries = read_dir(&ret)
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(2, entries.len());
+
+    fuzzable::test_fuzz("capture_ret", "target")
+        .unwrap()
+        .args(["--check-regressions=corpus"])
+        .logged_assert()
+        .success();
+
+    for entry in entries {
+        write(entry.path(), "Ret(0)").unwrap();
+    }
+
+    let tempdir = tempdir().unwrap();
+    let report = tempdir.path().join("report.xml");
+
+    fuzzable::test_fuzz("capture_ret", "target")
+        .unwrap()
+        .args([
+            "--check-regressions=corpus",
+            "--junit",
+            &report.to_string_lossy(),
+        ])
+        .logged_assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "Outcome differs from recorded outcome",
+        ));
+
+    // smoelius: The report is written before the regressions are reported.
+    let report = read_to_string(report).unwrap();
+    assert!(report.contains(r#"<testsuite name="target" tests="2" failures="2" errors="0""#));
+    assert!(report.contains("Outcome differs from recorded outcome"));
+
+    // smoelius: A corpus file whose arguments no longer deserialize is a regression too.
+    for entry in read_dir(&corpus).unwrap() {
+        write(entry.unwrap().path(), []).unwrap();
+    }
+
+    fuzzable::test_fuzz("capture_ret", "target")
+        .unwrap()
+        .args(["--check-regressions=corpus"])
+        .logged_assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "<arguments could not be deserialized>",
+        ));
+}
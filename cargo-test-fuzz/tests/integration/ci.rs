@@ -0,0 +1,40 @@
+use internal::dirs::output_directory_from_target;
+use predicates::prelude::*;
+use std::fs::{read_to_string, remove_dir_all};
+use tempfile::tempdir;
+use testing::{LoggedAssert, fuzzable, retry};
+
+const MAX_TOTAL_TIME: &str = "60";
+
+#[cfg_attr(dylint_lib = "general", allow(non_thread_safe_call_in_test))]
+#[test]
+fn ci() {
+    // smoelius: Remove the corpus too, so that `--ci` must auto-generate it.
+    let output_dir = output_directory_from_target("assert", "target");
+    remove_dir_all(output_dir).unwrap_or_default();
+
+    let tempdir = tempdir().unwrap();
+
+    retry(3, || {
+        fuzzable::test_fuzz("assert", "target")
+            .unwrap()
+            .current_dir(&tempdir)
+            .args([
+                "--ci",
+                "--run-until-crash",
+                "--max-total-time",
+                MAX_TOTAL_TIME,
+            ])
+            .logged_assert()
+            .try_failure()?
+            .try_stderr(predicate::str::contains(
+                "Found new crashes in 1 of 1 targets",
+            ))
+    })
+    .unwrap();
+
+    let report = read_to_string(tempdir.path().join("test-fuzz.xml")).unwrap();
+    assert!(report.contains(r#"<testsuites name="test-fuzz" tests="1" failures="1" errors="0">"#));
+    assert!(report.contains(r#"<testcase name="target" classname="assert""#));
+    assert!(report.contains(r#"<failure message="1 new crash: assertion failed: !x">"#));
+}
@@ -8,6 +8,63 @@ fn display_qwerty() {
     display("qwerty", "test", "target", "Args { data: \"asdfgh\" }", "");
 }
 
+#[test]
+fn display_std_conversion() {
+    display(
+        "std_conversion",
+        "test",
+        "target",
+        "Args { path: \"path\", os_str: \"os_str\", c_str: \"c_str\", cow: \"cow\", rc_ref_cell: \
+         RefCell { value: 1 }, arc_mutex: Mutex { data: 2, poisoned: false, .. }, arc_rw_lock: \
+         RwLock { data: 3, poisoned: false, .. } }",
+        "",
+    );
+}
+
+#[test]
+fn display_dyn_impls() {
+    display(
+        "dyn_impls",
+        "test",
+        "target",
+        "Args { shape: Square { side: 2 }, other: Rectangle { width: 1, height: 4 } }",
+        "",
+    );
+}
+
+#[test]
+fn display_dyn_impls_receiver() {
+    display(
+        "dyn_impls",
+        "test",
+        "Shape_is_empty",
+        "Args { self: Rectangle { width: 1, height: 4 } }",
+        "",
+    );
+}
+
+#[test]
+fn display_register_fn() {
+    display(
+        "register_fn",
+        "test",
+        "target",
+        "Args { f: register_fn::sum, xs: [1, 2, 3] }",
+        "",
+    );
+}
+
+#[test]
+fn display_roundtrip() {
+    display(
+        "roundtrip",
+        "test",
+        "target",
+        "Args { value: [1, 2, 65535] }",
+        "",
+    );
+}
+
 #[test]
 fn display_debug_crash() {
     display(
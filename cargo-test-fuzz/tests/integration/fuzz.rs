@@ -44,4 +44,17 @@ fn fuzz(krate: &str, persistent: bool) {
             .try_code(predicate::eq(1))
     })
     .unwrap();
+
+    fuzzable::test_fuzz(krate, "target")
+        .unwrap()
+        .args(["--status"])
+        .logged_assert()
+        .success()
+        .stdout(
+            predicate::str::is_match(
+                r"(?m)^TARGET +EXECS/SEC +CORPUS +COVERAGE +CRASHES +HANGS +LAST NEW FIND$",
+            )
+            .unwrap(),
+        )
+        .stdout(predicate::str::is_match(r"(?m)^target +\d+ +\d+ +\d+\.\d\d% +[1-9]\d* ").unwrap());
 }
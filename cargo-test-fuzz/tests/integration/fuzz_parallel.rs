@@ -1,4 +1,6 @@
-use internal::dirs::{corpus_directory_from_target, output_directory_from_target};
+use internal::dirs::{
+    corpus_directory_from_target, crashes_directory_from_target, output_directory_from_target,
+};
 use predicates::prelude::*;
 use std::fs::remove_dir_all;
 use testing::{LoggedAssert, fuzzable, retry};
@@ -51,6 +53,84 @@ fn fuzz_parallel() {
     }
 }
 
+const ADAPTIVE_MAX_TOTAL_TIME: &str = "60";
+const ADAPTIVE_SLICE: &str = "5";
+
+#[cfg_attr(dylint_lib = "general", allow(non_thread_safe_call_in_test))]
+#[test]
+fn fuzz_adaptive() {
+    for i in 0..6 {
+        let output_dir = output_directory_from_target("parallel", &format!("target_{i}"));
+        remove_dir_all(output_dir).unwrap_or_default();
+    }
+
+    fuzzable::test("parallel", "test")
+        .unwrap()
+        .logged_assert()
+        .success();
+
+    // smoelius: Each target takes a `bool`, so each should stop finding new inputs almost
+    // immediately and be retired after its first slice.
+    let assert = retry(3, || {
+        fuzzable::test_fuzz_inexact("parallel", "target")
+            .unwrap()
+            .args([
+                "--exit-code",
+                "--cpus",
+                "1",
+                "--max-total-time",
+                ADAPTIVE_MAX_TOTAL_TIME,
+                "--slice",
+                ADAPTIVE_SLICE,
+                "--retire-after",
+                "1",
+            ])
+            .logged_assert()
+            .try_code(predicate::eq(0))
+    })
+    .unwrap();
+
+    assert
+        .stderr(predicate::str::contains("Retiring target target_0"))
+        .stderr(predicate::str::contains("All targets retired"));
+}
+
+const JOBS: &str = "2";
+
+#[cfg_attr(dylint_lib = "general", allow(non_thread_safe_call_in_test))]
+#[test]
+fn fuzz_jobs() {
+    let output_dir = output_directory_from_target("assert", "target");
+    remove_dir_all(&output_dir).unwrap_or_default();
+
+    fuzzable::test("assert", "test")
+        .unwrap()
+        .logged_assert()
+        .success();
+
+    retry(3, || {
+        fuzzable::test_fuzz("assert", "target")
+            .unwrap()
+            .args([
+                "--exit-code",
+                "--run-until-crash",
+                "--cpus",
+                CPUS,
+                "--jobs",
+                JOBS,
+            ])
+            .logged_assert()
+            .try_code(predicate::eq(1))
+    })
+    .unwrap();
+
+    assert!(output_dir.join("default").exists());
+    assert!(output_dir.join("secondary1").exists());
+
+    // smoelius: Each instance has its own `crashes` directory.
+    assert_eq!(2, crashes_directory_from_target("assert", "target").len());
+}
+
 const MAX_TOTAL_TIME: &str = "10";
 
 #[cfg_attr(dylint_lib = "general", allow(non_thread_safe_call_in_test))]
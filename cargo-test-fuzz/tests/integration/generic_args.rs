@@ -1,4 +1,5 @@
 use internal::dirs::{generic_args_directory_from_target, impl_generic_args_directory_from_target};
+use predicates::prelude::*;
 use std::fs::remove_dir_all;
 use testing::{LoggedAssert, fuzzable};
 
@@ -7,12 +8,17 @@ use testing::{LoggedAssert, fuzzable};
 fn generic() {
     let impl_expected = ["generic::Bar", "generic::Foo"];
     let expected = ["generic::Baz<generic::Bar>", "generic::Baz<generic::Foo>"];
+    let suggestion = r#"multiple impl instantiations recorded; choose one of:
+    #[test_fuzz(impl_generic_args = "crate::Bar", generic_args = ["crate::Baz<crate::Bar>", "crate::Baz<crate::Foo>"])]
+    #[test_fuzz(impl_generic_args = "crate::Foo", generic_args = ["crate::Baz<crate::Bar>", "crate::Baz<crate::Foo>"])]
+"#;
     test(
         "generic",
         "test_bound",
         "Struct_target_bound",
         &impl_expected,
         &expected,
+        suggestion,
     );
     test(
         "generic",
@@ -20,6 +26,7 @@ fn generic() {
         "Struct_target_where_clause",
         &impl_expected,
         &expected,
+        suggestion,
     );
     test(
         "generic",
@@ -27,6 +34,7 @@ fn generic() {
         "Struct_target_only_generic_args",
         &impl_expected,
         &expected,
+        suggestion,
     );
 }
 
@@ -35,17 +43,32 @@ fn generic() {
 fn unserde() {
     let impl_expected = [""];
     let expected = ["unserde::Struct"];
-    test("unserde", "test", "target", &impl_expected, &expected);
+    test(
+        "unserde",
+        "test",
+        "target",
+        &impl_expected,
+        &expected,
+        "target: #[test_fuzz(generic_args = \"crate::Struct\")]",
+    );
     test(
         "unserde",
         "test_in_production",
         "target_in_production",
         &impl_expected,
         &expected,
+        "target_in_production: #[test_fuzz(generic_args = \"crate::Struct\")]",
     );
 }
 
-fn test(krate: &str, test: &str, target: &str, impl_expected: &[&str], expected: &[&str]) {
+fn test(
+    krate: &str,
+    test: &str,
+    target: &str,
+    impl_expected: &[&str],
+    expected: &[&str],
+    suggestion: &str,
+) {
     let impl_generic_args = impl_generic_args_directory_from_target(krate, target);
 
     // smoelius: `corpus` is distinct for all tests. So there is no race here.
@@ -57,6 +80,16 @@ fn test(krate: &str, test: &str, target: &str, impl_expected: &[&str], expected:
     #[cfg_attr(dylint_lib = "general", allow(non_thread_safe_call_in_test))]
     remove_dir_all(generic_args).unwrap_or_default();
 
+    // smoelius: Missing recordings produce a warning, not an error.
+    fuzzable::test_fuzz(krate, target)
+        .unwrap()
+        .args(["--suggest-generic-args"])
+        .logged_assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "Did you remember to run `cargo test`?",
+        ));
+
     fuzzable::test(krate, test)
         .unwrap()
         .logged_assert()
@@ -85,4 +118,11 @@ fn test(krate: &str, test: &str, target: &str, impl_expected: &[&str], expected:
 
         assert_eq!(expected, &actual);
     }
+
+    fuzzable::test_fuzz(krate, target)
+        .unwrap()
+        .args(["--suggest-generic-args"])
+        .logged_assert()
+        .success()
+        .stdout(predicate::str::contains(suggestion));
 }
@@ -0,0 +1,37 @@
+use internal::dirs::corpus_directory_from_target;
+use predicates::prelude::*;
+use std::fs::{read_dir, remove_dir_all};
+use testing::{LoggedAssert, fuzzable};
+
+const TARGETS: [&str; 3] = ["target_u8", "target_String", "target_Vec_u64"];
+
+#[cfg_attr(dylint_lib = "general", allow(non_thread_safe_call_in_test))]
+#[test]
+fn generic_list() {
+    for target in TARGETS {
+        let corpus = corpus_directory_from_target("generic_list", target);
+        remove_dir_all(corpus).unwrap_or_default();
+    }
+
+    fuzzable::test("generic_list", "test")
+        .unwrap()
+        .logged_assert()
+        .success();
+
+    // smoelius: Each instantiation should have recorded exactly the one call made with its type.
+    for target in TARGETS {
+        let corpus = corpus_directory_from_target("generic_list", target);
+        assert_eq!(1, read_dir(corpus).unwrap().count());
+    }
+
+    fuzzable::test_fuzz_inexact("generic_list", "target")
+        .unwrap()
+        .args(["--list"])
+        .logged_assert()
+        .success()
+        .stdout(
+            predicate::str::contains("\"target_u8\"")
+                .and(predicate::str::contains("\"target_String\""))
+                .and(predicate::str::contains("\"target_Vec_u64\"")),
+        );
+}
@@ -0,0 +1,52 @@
+use internal::dirs::output_directory_from_target;
+use predicates::prelude::*;
+use std::fs::{read_to_string, remove_dir_all};
+use tempfile::tempdir;
+use testing::{LoggedAssert, fuzzable, retry};
+
+const MAX_TOTAL_TIME: &str = "60";
+
+#[cfg_attr(dylint_lib = "general", allow(non_thread_safe_call_in_test))]
+#[test]
+fn on_crash() {
+    let output_dir = output_directory_from_target("assert", "target");
+    remove_dir_all(output_dir).unwrap_or_default();
+
+    fuzzable::test("assert", "test")
+        .unwrap()
+        .logged_assert()
+        .success();
+
+    let tempdir = tempdir().unwrap();
+    let log = tempdir.path().join("log.txt");
+    let command = format!(
+        "printf '%s|%s|%s|%s\\n' \"$TEST_FUZZ_HOOK_EVENT\" \"$TEST_FUZZ_HOOK_CRATE\" \
+         \"$TEST_FUZZ_HOOK_TARGET\" \"$TEST_FUZZ_HOOK_PANIC_MESSAGE\" >> '{}'",
+        log.to_string_lossy()
+    );
+
+    retry(3, || {
+        fuzzable::test_fuzz("assert", "target")
+            .unwrap()
+            .args([
+                "--exit-code",
+                "--run-until-crash",
+                "--max-total-time",
+                MAX_TOTAL_TIME,
+                "--on-crash",
+                &command,
+            ])
+            .logged_assert()
+            .try_code(predicate::eq(1))
+    })
+    .unwrap();
+
+    // smoelius: The hook for the crash that stopped fuzzing is run before `cargo test-fuzz` exits.
+    let contents = read_to_string(&log).unwrap();
+    assert!(
+        contents
+            .lines()
+            .any(|line| line == "crash|assert|target|assertion failed: !x"),
+        "{contents}"
+    );
+}
@@ -0,0 +1,44 @@
+use internal::dirs::{corpus_directory_from_target, output_directory_from_target};
+use std::fs::{copy, create_dir_all, read_dir, read_to_string, remove_dir_all};
+use tempfile::tempdir;
+use testing::{LoggedAssert, fuzzable};
+
+#[cfg_attr(dylint_lib = "general", allow(non_thread_safe_call_in_test))]
+#[test]
+fn replay_crashes() {
+    let corpus = corpus_directory_from_target("calm_and_panicky", "panicky");
+    let output_dir = output_directory_from_target("calm_and_panicky", "panicky");
+    remove_dir_all(&corpus).unwrap_or_default();
+    remove_dir_all(&output_dir).unwrap_or_default();
+
+    fuzzable::test("calm_and_panicky", "test")
+        .unwrap()
+        .logged_assert()
+        .success();
+
+    // smoelius: Every input to `panicky` crashes. So rather than fuzz, make the corpus file a
+    // crash.
+    let crashes = output_dir.join("default/crashes");
+    create_dir_all(&crashes).unwrap();
+    let entry = read_dir(corpus).unwrap().next().unwrap().unwrap();
+    copy(entry.path(), crashes.join("id:000000")).unwrap();
+
+    let tempdir = tempdir().unwrap();
+    let report = tempdir.path().join("report.xml");
+
+    fuzzable::test_fuzz("calm_and_panicky", "panicky")
+        .unwrap()
+        .args(["--replay=crashes", "--junit", &report.to_string_lossy()])
+        .logged_assert()
+        .success();
+
+    let report = read_to_string(report).unwrap();
+    assert!(
+        report.contains(r#"<testsuite name="panicky" tests="1" failures="1" errors="0""#),
+        "{report}"
+    );
+    assert!(
+        report.contains(r#"<failure message="explicit panic">"#),
+        "{report}"
+    );
+}
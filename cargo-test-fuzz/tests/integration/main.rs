@@ -1,6 +1,15 @@
+mod apply_generic_args;
 mod auto_generate;
+mod batch;
 mod build;
+mod capture_globals;
+mod capture_hook;
+mod changed_since;
+mod check;
+mod check_regressions;
+mod ci;
 mod consolidate;
+mod differential;
 mod display;
 mod fuzz;
 mod fuzz_cast;
@@ -8,5 +17,12 @@ mod fuzz_generic;
 mod fuzz_parallel;
 mod fuzz_profile;
 mod generic_args;
+mod generic_list;
+mod hooks;
+mod junit;
 mod replay;
+mod replay_parallel;
+mod sequence;
+mod shared;
+mod stats_file;
 mod warning;
@@ -0,0 +1,62 @@
+use internal::dirs::corpus_directory_from_target;
+use std::fs::{read_dir, remove_dir_all};
+use testing::{LoggedAssert, fuzzable};
+
+const CPUS: &str = "2";
+
+#[cfg_attr(dylint_lib = "general", allow(non_thread_safe_call_in_test))]
+#[test]
+fn replay_parallel() {
+    let corpus = corpus_directory_from_target("mixed", "target");
+    remove_dir_all(&corpus).unwrap_or_default();
+
+    fuzzable::test("mixed", "test")
+        .unwrap()
+        .logged_assert()
+        .success();
+
+    let mut file_names = read_dir(&corpus)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect::<Vec<_>>();
+    file_names.sort();
+    assert_eq!(4, file_names.len());
+
+    // smoelius: A failing file does not cause replay to fail.
+    let assert = fuzzable::test_fuzz("mixed", "target")
+        .unwrap()
+        .args(["--replay=corpus", "--cpus", CPUS])
+        .logged_assert()
+        .success();
+
+    // smoelius: Each file's result starts with a line beginning with the file's name. The results
+    // must be printed in order, regardless of which worker finished first.
+    let stdout = std::str::from_utf8(&assert.get_output().stdout).unwrap();
+    let mut results = Vec::<(&str, String)>::new();
+    for line in stdout.lines() {
+        if let Some((file_name, rest)) = line.split_once(": ")
+            && file_names.iter().any(|other| other == file_name)
+        {
+            results.push((file_name, rest.to_owned()));
+        } else if let Some((_, output)) = results.last_mut() {
+            output.push('\n');
+            output.push_str(line);
+        }
+    }
+    assert_eq!(
+        file_names,
+        results
+            .iter()
+            .map(|&(file_name, _)| file_name)
+            .collect::<Vec<_>>(),
+        "{stdout}"
+    );
+    assert_eq!(
+        1,
+        results
+            .iter()
+            .filter(|(_, output)| output.contains("x is three"))
+            .count(),
+        "{stdout}"
+    );
+}
@@ -0,0 +1,45 @@
+use internal::dirs::corpus_directory_from_target;
+use predicates::prelude::*;
+use std::fs::{read_dir, remove_dir_all};
+use testing::{LoggedAssert, fuzzable, retry};
+
+const MAX_TOTAL_TIME: &str = "60";
+
+#[cfg_attr(dylint_lib = "general", allow(non_thread_safe_call_in_test))]
+#[test]
+fn sequence() {
+    let corpus = corpus_directory_from_target("sequence", "Buffer_sequence");
+    remove_dir_all(&corpus).unwrap_or_default();
+
+    fuzzable::test("sequence", "test")
+        .unwrap()
+        .logged_assert()
+        .success();
+
+    // smoelius: Each receiver's sequence should be written once, when the receiver is dropped.
+    assert_eq!(2, read_dir(&corpus).unwrap().count());
+
+    fuzzable::test_fuzz("sequence", "Buffer_sequence")
+        .unwrap()
+        .args(["--display=corpus"])
+        .logged_assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "[insert { x: 1 }, insert { x: 2 }, remove, flush, len]",
+        ))
+        .stdout(predicate::str::contains("[insert { x: 3 }, len]"));
+
+    retry(3, || {
+        fuzzable::test_fuzz("sequence", "Buffer_sequence")
+            .unwrap()
+            .args([
+                "--exit-code",
+                "--run-until-crash",
+                "--max-total-time",
+                MAX_TOTAL_TIME,
+            ])
+            .logged_assert()
+            .try_code(predicate::eq(1))
+    })
+    .unwrap();
+}
@@ -0,0 +1,48 @@
+use internal::dirs::corpus_directory_from_target;
+use predicates::prelude::*;
+use std::fs::{read, read_dir, remove_dir_all};
+use testing::{LoggedAssert, fuzzable};
+
+#[cfg_attr(dylint_lib = "general", allow(non_thread_safe_call_in_test))]
+#[test]
+fn shared() {
+    let corpus = corpus_directory_from_target("shared", "target");
+    remove_dir_all(corpus).unwrap_or_default();
+
+    fuzzable::test("shared", "test")
+        .unwrap()
+        .logged_assert()
+        .success();
+
+    fuzzable::test_fuzz("shared", "target")
+        .unwrap()
+        .args(["--replay=corpus"])
+        .logged_assert()
+        .success()
+        .stdout(predicate::str::contains("Ret(true)"));
+}
+
+#[cfg_attr(dylint_lib = "general", allow(non_thread_safe_call_in_test))]
+#[test]
+fn not_shared() {
+    for target in ["single", "plain"] {
+        let corpus = corpus_directory_from_target("shared", target);
+        remove_dir_all(corpus).unwrap_or_default();
+    }
+
+    fuzzable::test("shared", "test")
+        .unwrap()
+        .logged_assert()
+        .success();
+
+    let [single, plain] = ["single", "plain"].map(|target| {
+        let corpus = corpus_directory_from_target("shared", target);
+        let entries = read_dir(corpus)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect::<Vec<_>>();
+        assert_eq!(1, entries.len());
+        read(&entries[0]).unwrap()
+    });
+    assert_eq!(plain, single);
+}
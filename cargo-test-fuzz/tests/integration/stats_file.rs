@@ -0,0 +1,53 @@
+use internal::dirs::output_directory_from_target;
+use predicates::prelude::*;
+use std::fs::{read_to_string, remove_dir_all};
+use tempfile::tempdir;
+use testing::{LoggedAssert, fuzzable, retry};
+
+const MAX_TOTAL_TIME: &str = "60";
+
+#[cfg_attr(dylint_lib = "general", allow(non_thread_safe_call_in_test))]
+#[test]
+fn stats_file() {
+    let output_dir = output_directory_from_target("assert", "target");
+    remove_dir_all(output_dir).unwrap_or_default();
+
+    fuzzable::test("assert", "test")
+        .unwrap()
+        .logged_assert()
+        .success();
+
+    let tempdir = tempdir().unwrap();
+    let stats_file = tempdir.path().join("stats.jsonl");
+
+    retry(3, || {
+        fuzzable::test_fuzz("assert", "target")
+            .unwrap()
+            .args([
+                "--exit-code",
+                "--run-until-crash",
+                "--max-total-time",
+                MAX_TOTAL_TIME,
+                "--stats-file",
+                &stats_file.to_string_lossy(),
+            ])
+            .logged_assert()
+            .try_code(predicate::eq(1))
+    })
+    .unwrap();
+
+    // smoelius: A snapshot is written when fuzzing starts and when it stops. The latter should
+    // include the main instance's statistics.
+    let contents = read_to_string(&stats_file).unwrap();
+    let last = contents.lines().last().unwrap();
+    let snapshot = serde_json::from_str::<serde_json::Value>(last).unwrap();
+    assert_eq!("assert", snapshot["crate"]);
+    assert_eq!("target", snapshot["target"]);
+    assert!(snapshot["instances"]["default"]["corpus_count"].is_u64());
+    assert!(
+        snapshot["instances"]["default"]["saved_crashes"]
+            .as_u64()
+            .unwrap()
+            >= 1
+    );
+}
@@ -0,0 +1,6 @@
+//! Targets that are called only from integration tests. See `tests/capture_hook.rs`.
+
+#[test_fuzz::test_fuzz]
+pub fn library_target(x: u8) {
+    assert_ne!(x, u8::MAX);
+}
@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    cell::RefCell,
+    sync::{
+        Mutex,
+        atomic::{AtomicU32, Ordering},
+    },
+};
+
+#[derive(Clone, Deserialize, Serialize)]
+struct Config {
+    scale: u32,
+}
+
+static CONFIG: Mutex<Config> = Mutex::new(Config { scale: 1 });
+
+static COUNT: AtomicU32 = AtomicU32::new(0);
+
+thread_local! {
+    static CACHE: RefCell<Vec<u32>> = const { RefCell::new(Vec::new()) };
+}
+
+#[test_fuzz::test_fuzz(capture_globals = "CONFIG, COUNT, CACHE")]
+fn target(x: u32) -> u32 {
+    let scale = CONFIG.lock().unwrap().scale;
+    let count = COUNT.load(Ordering::SeqCst);
+    let sum = CACHE.with_borrow(|cache| cache.iter().sum::<u32>());
+    x * scale + count + sum
+}
+
+#[test]
+fn test() {
+    CONFIG.lock().unwrap().scale = 100;
+    COUNT.store(20, Ordering::SeqCst);
+    CACHE.with_borrow_mut(|cache| cache.extend([1, 2]));
+    assert_eq!(123, target(1));
+}
@@ -0,0 +1,8 @@
+use test_fuzz_fuzzable::library_target;
+
+test_fuzz::capture_hook!();
+
+#[test]
+fn test() {
+    library_target(1);
+}
@@ -0,0 +1,13 @@
+use std::panic::catch_unwind;
+
+#[test_fuzz::test_fuzz(capture_ret)]
+fn target(x: u32) -> u32 {
+    assert!(x != 0, "zero");
+    100 / x
+}
+
+#[test]
+fn test() {
+    assert_eq!(25, target(4));
+    assert!(catch_unwind(|| target(0)).is_err());
+}
@@ -0,0 +1,19 @@
+#[test_fuzz::test_fuzz(check = "halves_to_arg")]
+fn target(x: u8) -> u8 {
+    x.wrapping_mul(2)
+}
+
+#[allow(clippy::trivially_copy_pass_by_ref)]
+fn halves_to_arg((x,): (&u8,), ret: &u8) -> bool {
+    ret / 2 == *x
+}
+
+#[test]
+fn test() {
+    assert_eq!(6, target(3));
+}
+
+#[test]
+fn test_violation() {
+    assert_eq!(144, target(200));
+}
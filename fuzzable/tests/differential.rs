@@ -0,0 +1,24 @@
+#[test_fuzz::test_fuzz(differential = "reference")]
+fn target(xs: &[u8]) -> u32 {
+    xs.iter().copied().map(u32::from).sum()
+}
+
+// smoelius: `reference` ignores all but the first three elements, so that the two functions
+// disagree on longer inputs.
+fn reference(xs: &[u8]) -> u32 {
+    let mut sum = 0;
+    for &x in xs.iter().take(3) {
+        sum += u32::from(x);
+    }
+    sum
+}
+
+#[test]
+fn test() {
+    assert_eq!(6, target(&[1, 2, 3]));
+}
+
+#[test]
+fn test_divergent() {
+    assert_eq!(10, target(&[1, 2, 3, 4]));
+}
@@ -0,0 +1,151 @@
+use serde::{Deserialize, Serialize};
+use std::any::Any;
+
+trait Shape: Any {
+    fn area(&self) -> u64;
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct Square {
+    side: u32,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct Rectangle {
+    width: u32,
+    height: u32,
+}
+
+impl Shape for Square {
+    fn area(&self) -> u64 {
+        u64::from(self.side) * u64::from(self.side)
+    }
+}
+
+impl Shape for Rectangle {
+    fn area(&self) -> u64 {
+        u64::from(self.width) * u64::from(self.height)
+    }
+}
+
+#[test_fuzz::test_fuzz(dyn_impls = "dyn Shape: Square, Rectangle")]
+fn target(shape: &dyn Shape, other: Box<dyn Shape>) -> bool {
+    shape.area() == other.area()
+}
+
+#[test_fuzz::test_fuzz_impl]
+impl dyn Shape {
+    #[test_fuzz::test_fuzz(dyn_impls = "dyn Shape: Square, Rectangle")]
+    fn is_empty(&self) -> bool {
+        self.area() == 0
+    }
+}
+
+#[test]
+fn test() {
+    assert!(target(
+        &Square { side: 2 },
+        Box::new(Rectangle {
+            width: 1,
+            height: 4
+        })
+    ));
+    assert!(!<dyn Shape>::is_empty(&Rectangle {
+        width: 1,
+        height: 4
+    }));
+}
+
+// smoelius: Traits and implementors with the same names, but in different modules, must not
+// produce colliding enums or variants.
+mod a {
+    pub trait Handler: std::any::Any {
+        fn handle(&self) -> u32;
+    }
+
+    #[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+    pub struct Echo(pub u32);
+
+    impl Handler for Echo {
+        fn handle(&self) -> u32 {
+            self.0
+        }
+    }
+}
+
+mod b {
+    pub trait Handler: std::any::Any {
+        fn handle(&self) -> u32;
+    }
+
+    #[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+    pub struct Echo(pub u32);
+
+    impl Handler for Echo {
+        fn handle(&self) -> u32 {
+            self.0
+        }
+    }
+
+    impl Handler for super::a::Echo {
+        fn handle(&self) -> u32 {
+            self.0
+        }
+    }
+}
+
+#[test_fuzz::test_fuzz(
+    dyn_impls = "dyn a::Handler: a::Echo",
+    dyn_impls = "dyn b::Handler: a::Echo, b::Echo"
+)]
+fn handlers(x: &dyn a::Handler, y: &dyn b::Handler) -> bool {
+    x.handle() == y.handle()
+}
+
+#[test]
+fn test_handlers() {
+    assert!(handlers(&a::Echo(1), &b::Echo(1)));
+    assert!(handlers(&a::Echo(1), &a::Echo(1)));
+}
+
+// smoelius: Paths whose segments join to the same string (`c_d::Echo` and `c::d_Echo`), and paths
+// with raw identifiers, must also produce distinct, valid enum and variant names.
+mod c_d {
+    #[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+    pub struct Echo(pub u32);
+}
+
+mod c {
+    #[allow(non_camel_case_types)]
+    #[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+    pub struct d_Echo(pub u32);
+}
+
+mod r#type {
+    pub trait Handler: std::any::Any {
+        fn handle(&self) -> u32;
+    }
+
+    impl Handler for super::c_d::Echo {
+        fn handle(&self) -> u32 {
+            self.0
+        }
+    }
+
+    impl Handler for super::c::d_Echo {
+        fn handle(&self) -> u32 {
+            self.0
+        }
+    }
+}
+
+#[test_fuzz::test_fuzz(dyn_impls = "dyn r#type::Handler: c_d::Echo, c::d_Echo")]
+fn raw_handler(x: &dyn r#type::Handler) -> u32 {
+    x.handle()
+}
+
+#[test]
+fn test_raw_handler() {
+    assert_eq!(1, raw_handler(&c_d::Echo(1)));
+    assert_eq!(2, raw_handler(&c::d_Echo(2)));
+}
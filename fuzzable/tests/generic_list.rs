@@ -0,0 +1,14 @@
+use serde::{Serialize, de::DeserializeOwned};
+use std::fmt::Debug;
+
+#[test_fuzz::test_fuzz(generic_args = ["u8", "String", "Vec<u64>"])]
+fn target<T: Clone + Debug + DeserializeOwned + Serialize>(x: &T) {
+    assert!(!format!("{x:?}").contains("qwerty"));
+}
+
+#[test]
+fn test() {
+    target(&0u8);
+    target(&String::from("x"));
+    target(&vec![1u64, 2, 3]);
+}
@@ -0,0 +1,27 @@
+//! The function [`target`] intentionally panics when its argument is three. The function [`pair`]
+//! intentionally panics when its argument is true.
+
+#[test_fuzz::test_fuzz]
+fn target(x: u8) {
+    assert!(x != 3, "x is three");
+}
+
+#[test_fuzz::test_fuzz]
+fn pair(x: bool) {
+    assert!(!x, "x is true");
+}
+
+#[test]
+#[should_panic = "x is three"]
+fn test() {
+    for x in 0..4 {
+        target(x);
+    }
+}
+
+#[test]
+#[should_panic = "x is true"]
+fn test_pair() {
+    pair(false);
+    pair(true);
+}
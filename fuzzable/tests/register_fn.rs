@@ -0,0 +1,37 @@
+#[test_fuzz::register_fn]
+const fn len(xs: &[u8]) -> usize {
+    xs.len()
+}
+
+#[test_fuzz::register_fn]
+fn sum(xs: &[u8]) -> usize {
+    xs.iter().copied().map(usize::from).sum()
+}
+
+#[test_fuzz::test_fuzz]
+fn target(f: fn(&[u8]) -> usize, xs: &[u8]) -> usize {
+    f(xs)
+}
+
+// smoelius: `Checksum` does not implement `Default`. It is required only to replay function
+// pointers that were not registered.
+#[derive(Debug, PartialEq)]
+struct Checksum(usize);
+
+#[test_fuzz::register_fn]
+fn checksum(xs: &[u8]) -> Checksum {
+    Checksum(sum(xs))
+}
+
+#[test_fuzz::test_fuzz]
+fn target_checksum(f: fn(&[u8]) -> Checksum, xs: &[u8]) -> Checksum {
+    f(xs)
+}
+
+#[test]
+fn test() {
+    assert_eq!(3, target(len, &[1, 2, 3]));
+    assert_eq!(6, target(sum, &[1, 2, 3]));
+    assert_eq!(1, target(|_| 1, &[]));
+    assert_eq!(Checksum(6), target_checksum(checksum, &[1, 2, 3]));
+}
@@ -0,0 +1,28 @@
+fn encode(xs: &[u16]) -> Vec<u8> {
+    xs.iter().flat_map(|x| x.to_le_bytes()).collect()
+}
+
+fn decode(bytes: &[u8]) -> Vec<u16> {
+    bytes
+        .chunks_exact(2)
+        .map(|chunk| u16::from_le_bytes([chunk[0], chunk[1]]))
+        .collect()
+}
+
+fn try_decode(bytes: &[u8]) -> Result<Vec<u16>, String> {
+    if bytes.len().is_multiple_of(2) {
+        Ok(decode(bytes))
+    } else {
+        Err(format!("odd length: {}", bytes.len()))
+    }
+}
+
+test_fuzz::roundtrip!(target, Vec<u16>, encode, decode);
+
+test_fuzz::roundtrip!(target_try, Vec<u16>, encode, try try_decode);
+
+#[test]
+fn test() {
+    target(vec![1, 2, 0xffff]);
+    target_try(vec![1, 2, 0xffff]);
+}
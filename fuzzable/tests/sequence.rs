@@ -0,0 +1,58 @@
+use serde::{Deserialize, Serialize};
+use test_fuzz::SequenceId;
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+struct Buffer {
+    pending: Vec<u8>,
+    flushed: Vec<u8>,
+    id: SequenceId,
+}
+
+#[test_fuzz::test_fuzz_impl(sequence = "new", sequence_id = "id")]
+impl Buffer {
+    fn new() -> Self {
+        Self::default()
+    }
+
+    #[test_fuzz::test_fuzz]
+    fn insert(&mut self, x: u8) {
+        self.pending.push(x);
+    }
+
+    // smoelius: `remove` does not handle the case where all values have been flushed. The bug is
+    // revealed only by calling `insert`, `flush`, and `remove`, in that order.
+    #[test_fuzz::test_fuzz]
+    fn remove(&mut self) -> u8 {
+        self.pending.pop().unwrap_or_else(|| {
+            self.flushed
+                .pop()
+                .map_or(0, |_| panic!("remove after flush"))
+        })
+    }
+
+    #[test_fuzz::test_fuzz]
+    fn flush(&mut self) {
+        self.flushed.append(&mut self.pending);
+    }
+
+    #[test_fuzz::test_fuzz]
+    fn len(&self) -> usize {
+        self.pending.len() + self.flushed.len()
+    }
+}
+
+// smoelius: Moving a receiver changes its address, but not its sequence. Calls on a second
+// receiver are interleaved with calls on the first, and are recorded in a separate sequence.
+#[test]
+fn test() {
+    let mut buffer = Buffer::new();
+    let mut other = Buffer::new();
+    buffer.insert(1);
+    other.insert(3);
+    let mut buffer = Box::new(buffer);
+    buffer.insert(2);
+    assert_eq!(1, other.len());
+    assert_eq!(2, buffer.remove());
+    buffer.flush();
+    assert_eq!(1, buffer.len());
+}
@@ -0,0 +1,28 @@
+use std::{cell::RefCell, rc::Rc, sync::Arc};
+
+#[test_fuzz::test_fuzz]
+fn target(x: Rc<RefCell<u32>>, y: Rc<RefCell<u32>>, z: Arc<String>, w: Arc<String>) -> bool {
+    *x.borrow_mut() += 1;
+    *y.borrow() == 1 && Arc::ptr_eq(&z, &w)
+}
+
+// smoelius: With only one `Rc` argument, there is no sharing to preserve. So `single` and `plain`
+// should produce identical corpus files.
+#[test_fuzz::test_fuzz(no_auto_generate)]
+fn single(x: Rc<u32>) -> u32 {
+    *x
+}
+
+#[test_fuzz::test_fuzz(no_auto_generate)]
+fn plain(x: u32) -> u32 {
+    x
+}
+
+#[test]
+fn test() {
+    let rc = Rc::new(RefCell::new(0));
+    let arc = Arc::new(String::from("shared"));
+    assert!(target(rc.clone(), rc, arc.clone(), arc));
+    assert_eq!(1, single(Rc::new(1)));
+    assert_eq!(1, plain(1));
+}
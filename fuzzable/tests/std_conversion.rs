@@ -0,0 +1,60 @@
+use std::{
+    borrow::Cow,
+    cell::RefCell,
+    ffi::{CStr, OsStr},
+    path::Path,
+    rc::Rc,
+    sync::{Arc, Mutex, RwLock},
+};
+
+#[test_fuzz::test_fuzz]
+fn target(
+    path: &Path,
+    os_str: &OsStr,
+    c_str: &CStr,
+    cow: Cow<'_, str>,
+    rc_ref_cell: Rc<RefCell<u32>>,
+    arc_mutex: Arc<Mutex<u32>>,
+    arc_rw_lock: Arc<RwLock<u32>>,
+) {
+    println!(
+        "{path:?} {os_str:?} {c_str:?} {cow:?} {} {} {}",
+        rc_ref_cell.borrow(),
+        arc_mutex.lock().unwrap(),
+        arc_rw_lock.read().unwrap(),
+    );
+}
+
+#[test_fuzz::test_fuzz]
+fn target_full_paths(path: &std::path::Path, rc_ref_cell: std::rc::Rc<std::cell::RefCell<u32>>) {
+    println!("{path:?} {}", rc_ref_cell.borrow());
+}
+
+mod user_types {
+    use serde::{Deserialize, Serialize};
+
+    // smoelius: With `no_std_conversions`, a type that merely shares a name with a standard
+    // library type is not treated specially.
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    pub struct Path(pub String);
+
+    #[test_fuzz::test_fuzz(no_std_conversions)]
+    pub fn target(path: &Path) {
+        println!("{path:?}");
+    }
+}
+
+#[test]
+fn test() {
+    target(
+        Path::new("path"),
+        OsStr::new("os_str"),
+        c"c_str",
+        Cow::Borrowed("cow"),
+        Rc::new(RefCell::new(1)),
+        Arc::new(Mutex::new(2)),
+        Arc::new(RwLock::new(3)),
+    );
+    target_full_paths(std::path::Path::new("path"), Rc::new(RefCell::new(1)));
+    user_types::target(&user_types::Path(String::from("path")));
+}
@@ -2,10 +2,14 @@ use cargo_metadata::MetadataCommand;
 use std::{
     any::type_name,
     env,
-    path::PathBuf,
+    fs::read_dir,
+    path::{Path, PathBuf},
     sync::atomic::{AtomicBool, Ordering},
 };
 
+/// Name of the main fuzzer instance's subdirectory of a target's output directory
+pub const MAIN_INSTANCE: &str = "default";
+
 pub static IN_TEST: AtomicBool = AtomicBool::new(false);
 
 #[must_use]
@@ -39,18 +43,74 @@ pub fn corpus_directory_from_target(krate: &str, target: &str) -> PathBuf {
 }
 
 #[must_use]
-pub fn crashes_directory_from_target(krate: &str, target: &str) -> PathBuf {
-    output_directory_from_target(krate, target).join("default/crashes")
+pub fn ret_directory_from_args_type<T>() -> PathBuf {
+    ret_directory().join(path_from_args_type::<T>())
+}
+
+#[must_use]
+pub fn ret_directory_from_target(krate: &str, target: &str) -> PathBuf {
+    ret_directory().join(path_from_target(krate, target))
+}
+
+#[must_use]
+pub fn crashes_directory_from_target(krate: &str, target: &str) -> Vec<PathBuf> {
+    instance_subdirectories_from_target(krate, target, "crashes")
+}
+
+#[must_use]
+pub fn hangs_directory_from_target(krate: &str, target: &str) -> Vec<PathBuf> {
+    instance_subdirectories_from_target(krate, target, "hangs")
 }
 
 #[must_use]
-pub fn hangs_directory_from_target(krate: &str, target: &str) -> PathBuf {
-    output_directory_from_target(krate, target).join("default/hangs")
+pub fn queue_directory_from_target(krate: &str, target: &str) -> Vec<PathBuf> {
+    instance_subdirectories_from_target(krate, target, "queue")
 }
 
+/// Returns the directories of every fuzzer instance (e.g., `default`, `secondary1`) that has
+/// written to the target's output directory.
+///
+/// If no instance has, the main instance's directory is returned.
 #[must_use]
-pub fn queue_directory_from_target(krate: &str, target: &str) -> PathBuf {
-    output_directory_from_target(krate, target).join("default/queue")
+pub fn instance_directories_from_target(krate: &str, target: &str) -> Vec<PathBuf> {
+    instance_directories(&output_directory_from_target(krate, target))
+}
+
+/// Like [`instance_directories_from_target`], but takes the target's output directory.
+///
+/// Unlike [`output_directory_from_target`], this function does not run `cargo metadata`. So it is
+/// suitable for calling repeatedly, e.g., while fuzzing.
+#[must_use]
+pub fn instance_directories(output_dir: &Path) -> Vec<PathBuf> {
+    let mut dirs = read_dir(output_dir)
+        .into_iter()
+        .flatten()
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .collect::<Vec<_>>();
+    if dirs.is_empty() {
+        dirs.push(output_dir.join(MAIN_INSTANCE));
+    }
+    dirs.sort();
+    dirs
+}
+
+#[must_use]
+fn instance_subdirectories_from_target(krate: &str, target: &str, name: &str) -> Vec<PathBuf> {
+    let mut dirs = instance_directories_from_target(krate, target)
+        .into_iter()
+        .map(|dir| dir.join(name))
+        .filter(|path| path.is_dir())
+        .collect::<Vec<_>>();
+    if dirs.is_empty() {
+        dirs.push(
+            output_directory_from_target(krate, target)
+                .join(MAIN_INSTANCE)
+                .join(name),
+        );
+    }
+    dirs
 }
 
 #[must_use]
@@ -76,6 +136,12 @@ fn corpus_directory() -> PathBuf {
     target_directory(false, false).join(path_segment("corpus"))
 }
 
+#[must_use]
+fn ret_directory() -> PathBuf {
+    #[allow(clippy::disallowed_methods)]
+    target_directory(false, false).join(path_segment("ret"))
+}
+
 #[must_use]
 fn output_directory() -> PathBuf {
     #[allow(clippy::disallowed_methods)]
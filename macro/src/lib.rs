@@ -14,7 +14,6 @@ use quote::{ToTokens, quote};
 use std::{
     collections::{BTreeMap, BTreeSet},
     env::var,
-    str::FromStr,
     sync::{
         LazyLock,
         atomic::{AtomicU32, Ordering},
@@ -23,9 +22,12 @@ use std::{
 use syn::{
     Attribute, Block, Expr, Field, FieldValue, File, FnArg, FnModifiers, GenericArgument,
     GenericParam, Generics, Ident, ImplItem, ImplItemFn, ItemFn, ItemImpl, ItemMod, LifetimeParam,
-    PatType, Path, PathArguments, PathSegment, Receiver, ReceiverKind, ReturnType, Signature, Stmt,
-    Type, TypeParam, TypePath, TypeReference, TypeSlice, Visibility, WhereClause, WherePredicate,
-    parse::Parser, parse_macro_input, parse_quote, parse_str, parse2, punctuated::Punctuated,
+    Lit, LitStr, PatType, Path, PathArguments, PathSegment, Receiver, ReceiverKind, ReturnType,
+    Signature, Stmt, Type, TypeFnPtr, TypeParam, TypePath, TypeReference, TypeSlice, Visibility,
+    WhereClause, WherePredicate,
+    parse::{Parse, ParseStream, Parser},
+    parse_macro_input, parse_quote, parse_str, parse2,
+    punctuated::Punctuated,
     token,
 };
 
@@ -40,20 +42,114 @@ type Attrs = Vec<Attribute>;
 
 type Conversions = BTreeMap<OrdType, (Type, bool)>;
 
+// smoelius: Maps each trait object type named in a `dyn_impls` argument to the path of the enum
+// generated for it, and to whether the mapping was used.
+type DynImpls = BTreeMap<OrdType, (Path, bool)>;
+
 static CARGO_CRATE_NAME: LazyLock<String> =
     LazyLock::new(|| var("CARGO_CRATE_NAME").expect("Could not get `CARGO_CRATE_NAME`"));
 
+#[proc_macro_attribute]
+pub fn register_fn(args: TokenStream, item: TokenStream) -> TokenStream {
+    let item = parse_macro_input!(item as ItemFn);
+    expand_register_fn(args.into(), &item)
+        .unwrap_or_else(|error| item_with_error(&item, error))
+        .into()
+}
+
+fn expand_register_fn(args: TokenStream2, item: &ItemFn) -> syn::Result<TokenStream2> {
+    if !args.is_empty() {
+        return Err(syn::Error::new_spanned(
+            args,
+            "`register_fn` does not take arguments",
+        ));
+    }
+
+    let Signature {
+        constness: _,
+        asyncness,
+        safety,
+        abi,
+        fn_token,
+        ident,
+        generics,
+        paren_token: _,
+        inputs,
+        variadic: _,
+        output,
+    } = &item.sig;
+
+    if let Some(asyncness) = asyncness {
+        return Err(syn::Error::new_spanned(
+            asyncness,
+            "`register_fn` cannot be used with async functions",
+        ));
+    }
+    if !generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            generics,
+            "`register_fn` cannot be used with generic functions; consider registering a \
+             non-generic function that calls this one",
+        ));
+    }
+
+    let input_tys = inputs
+        .iter()
+        .map(|input| match input {
+            FnArg::Receiver(receiver) => Err(syn::Error::new_spanned(
+                receiver,
+                "`register_fn` cannot be used with methods; consider registering a free function \
+                 that calls this one",
+            )),
+            FnArg::Typed(PatType { ty, .. }) => Ok(ty),
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+    let fn_ty: Type = parse_quote! {
+        #safety #abi #fn_token ( #(#input_tys),* ) #output
+    };
+
+    let result = quote! {
+        #item
+
+        test_fuzz::runtime::inventory::submit! {
+            test_fuzz::runtime::RegisteredFn {
+                name: concat!(module_path!(), "::", stringify!(#ident)),
+                get: || std::boxed::Box::new(#ident as #fn_ty),
+            }
+        }
+    };
+    log(&result.to_token_stream());
+    Ok(result)
+}
+
 #[derive(FromMeta)]
-struct TestFuzzImplOpts {}
+struct TestFuzzImplOpts {
+    #[darling(default)]
+    sequence: Option<LitStr>,
+    #[darling(default)]
+    sequence_id: Option<LitStr>,
+}
 
 #[proc_macro_attribute]
 pub fn test_fuzz_impl(args: TokenStream, item: TokenStream) -> TokenStream {
-    let attr_args =
-        NestedMeta::parse_meta_list(args.into()).expect("Could not parse attribute args");
-    let _ =
-        TestFuzzImplOpts::from_list(&attr_args).expect("Could not parse `test_fuzz_impl` options");
-
     let item = parse_macro_input!(item as ItemImpl);
+    // smoelius: If an error occurs, emit the `impl` without its `test_fuzz` attributes. Otherwise,
+    // each attribute would be expanded on its own, producing errors unrelated to the original one.
+    let mut item_without_attrs = item.clone();
+    for impl_item in &mut item_without_attrs.items {
+        if let ImplItem::Fn(impl_item_fn) = impl_item {
+            impl_item_fn.attrs.retain(|attr| !is_test_fuzz(attr));
+        }
+    }
+    expand_test_fuzz_impl(args.into(), item)
+        .unwrap_or_else(|error| item_with_error(&item_without_attrs, error))
+        .into()
+}
+
+fn expand_test_fuzz_impl(args: TokenStream2, item: ItemImpl) -> syn::Result<TokenStream2> {
+    let attr_args = NestedMeta::parse_meta_list(args)?;
+    let opts = TestFuzzImplOpts::from_list(&attr_args)?;
+
     let ItemImpl {
         attrs,
         modifiers,
@@ -76,7 +172,47 @@ pub fn test_fuzz_impl(args: TokenStream, item: TokenStream) -> TokenStream {
         (Some(path.clone()), Some(quote! { #path #for_ }))
     });
 
-    let (impl_items, modules) = map_impl_items(&generics, trait_path.as_ref(), &self_ty, &items);
+    match (&opts.sequence, &opts.sequence_id) {
+        (Some(sequence), None) => {
+            return Err(syn::Error::new(
+                sequence.span(),
+                "`sequence` requires `sequence_id`",
+            ));
+        }
+        (None, Some(sequence_id)) => {
+            return Err(syn::Error::new(
+                sequence_id.span(),
+                "`sequence_id` requires `sequence`",
+            ));
+        }
+        _ => {}
+    }
+
+    let sequence_mod_ident = opts
+        .sequence
+        .as_ref()
+        .map(|_| {
+            if !generics.params.is_empty() {
+                return Err(syn::Error::new_spanned(
+                    &generics,
+                    "`sequence` cannot be used with a generic `impl` block",
+                ));
+            }
+            Ok(mod_ident(
+                &TestFuzzOpts::default(),
+                type_utils::type_base(&self_ty),
+                &Ident::new("sequence", Span::call_site()),
+            ))
+        })
+        .transpose()?;
+
+    let (impl_items, modules, sequenced) = map_impl_items(
+        &generics,
+        trait_path.as_ref(),
+        &self_ty,
+        sequence_mod_ident.as_ref(),
+        &items,
+    )?;
     if modules.is_empty() {
         let span = impl_token.span;
         let file = span.file();
@@ -87,46 +223,85 @@ pub fn test_fuzz_impl(args: TokenStream, item: TokenStream) -> TokenStream {
         );
     }
 
+    let sequence_module = opts
+        .sequence
+        .as_ref()
+        .zip(opts.sequence_id.as_ref())
+        .zip(sequence_mod_ident.as_ref())
+        .map(|((constructor, sequence_id), sequence_mod_ident)| {
+            sequence_module(
+                &self_ty,
+                constructor,
+                sequence_id,
+                sequence_mod_ident,
+                &sequenced,
+            )
+        })
+        .transpose()?;
+
     let result = quote! {
         #(#attrs)* #defaultness #unsafety #impl_token #generics #polarity #trait_ #self_ty #where_clause {
             #(#impl_items)*
         }
 
         #(#modules)*
+
+        #sequence_module
     };
     log(&result.to_token_stream());
-    result.into()
+    Ok(result)
 }
 
+/// A method whose calls are recorded as part of a sequence: the method's name, and the name of
+/// the module the `test_fuzz` macro generated for it.
+type Sequenced = (Ident, Ident);
+
 fn map_impl_items(
     generics: &Generics,
     trait_path: Option<&Path>,
     self_ty: &Type,
+    sequence_mod_ident: Option<&Ident>,
     items: &[ImplItem],
-) -> (Vec<ImplItem>, Vec<ItemMod>) {
+) -> syn::Result<(Vec<ImplItem>, Vec<ItemMod>, Vec<Sequenced>)> {
     let impl_items_modules = items
         .iter()
-        .map(map_impl_item(generics, trait_path, self_ty));
+        .map(map_impl_item(
+            generics,
+            trait_path,
+            self_ty,
+            sequence_mod_ident,
+        ))
+        .collect::<syn::Result<Vec<_>>>()?;
 
-    let (impl_items, modules): (Vec<_>, Vec<_>) = impl_items_modules.unzip();
+    let (impl_items, modules, sequenced): (Vec<_>, Vec<_>, Vec<_>) =
+        impl_items_modules.into_iter().multiunzip();
 
     let modules = modules.into_iter().flatten().collect();
 
-    (impl_items, modules)
+    let sequenced = sequenced.into_iter().flatten().collect();
+
+    Ok((impl_items, modules, sequenced))
 }
 
 fn map_impl_item<'a>(
     generics: &'a Generics,
     trait_path: Option<&'a Path>,
     self_ty: &'a Type,
-) -> impl Fn(&ImplItem) -> (ImplItem, Option<ItemMod>) + 'a {
+    sequence_mod_ident: Option<&'a Ident>,
+) -> impl Fn(&ImplItem) -> syn::Result<(ImplItem, Vec<ItemMod>, Option<Sequenced>)> + 'a {
     let generics = generics.clone();
     let self_ty = self_ty.clone();
     move |impl_item| {
         if let ImplItem::Fn(impl_item_fn) = &impl_item {
-            map_impl_item_fn(&generics, trait_path, &self_ty, impl_item_fn)
+            map_impl_item_fn(
+                &generics,
+                trait_path,
+                &self_ty,
+                sequence_mod_ident,
+                impl_item_fn,
+            )
         } else {
-            (impl_item.clone(), None)
+            Ok((impl_item.clone(), Vec::new(), None))
         }
     }
 }
@@ -139,8 +314,9 @@ fn map_impl_item_fn(
     generics: &Generics,
     trait_path: Option<&Path>,
     self_ty: &Type,
+    sequence_mod_ident: Option<&Ident>,
     impl_item_fn: &ImplItemFn,
-) -> (ImplItem, Option<ItemMod>) {
+) -> syn::Result<(ImplItem, Vec<ItemMod>, Option<Sequenced>)> {
     let ImplItemFn {
         attrs,
         vis,
@@ -152,22 +328,28 @@ fn map_impl_item_fn(
     let mut attrs = attrs.clone();
 
     attrs.iter().position(is_test_fuzz).map_or_else(
-        || (parse_quote!( #impl_item_fn ), None),
+        || Ok((parse_quote!( #impl_item_fn ), Vec::new(), None)),
         |i| {
             let attr = attrs.remove(i);
-            let opts = opts_from_attr(&attr);
-            let (method, module) = map_method_or_fn(
+            let opts = opts_from_attr(&attr)?;
+            let sequence_mod_ident = sequence_mod_ident.filter(|_| is_sequenceable(sig));
+            let (method, modules) = map_method_or_fn(
                 &generics.clone(),
                 trait_path,
                 Some(self_ty),
+                sequence_mod_ident,
                 &opts,
                 &attrs,
                 vis,
                 modifiers,
                 sig,
                 block,
-            );
-            (parse_quote!( #method ), Some(module))
+            )?;
+            let sequenced = sequence_mod_ident.map(|_| {
+                let mod_ident = mod_ident(&opts, type_utils::type_base(self_ty), &sig.ident);
+                (sig.ident.clone(), mod_ident)
+            });
+            Ok((parse_quote!( #method ), modules, sequenced))
         },
     )
 }
@@ -176,58 +358,239 @@ fn map_impl_item_fn(
 #[derive(Clone, Debug, Default, FromMeta)]
 struct TestFuzzOpts {
     #[darling(default)]
-    bounds: Option<String>,
+    bounds: Option<LitStr>,
+    #[darling(default)]
+    capture_globals: Option<LitStr>,
+    #[darling(default)]
+    capture_ret: bool,
+    #[darling(default)]
+    check: Option<LitStr>,
+    #[darling(multiple)]
+    convert: Vec<LitStr>,
+    #[darling(default)]
+    differential: Option<LitStr>,
     #[darling(multiple)]
-    convert: Vec<String>,
+    dyn_impls: Vec<LitStr>,
     #[darling(default)]
     enable_in_production: bool,
     #[darling(default)]
-    execute_with: Option<String>,
+    execute_with: Option<LitStr>,
     #[darling(default)]
-    generic_args: Option<String>,
+    generic_args: Option<GenericArgsOpt>,
     #[darling(default)]
-    impl_generic_args: Option<String>,
+    impl_generic_args: Option<LitStr>,
     #[darling(default)]
     no_auto_generate: bool,
     #[darling(default)]
+    no_std_conversions: bool,
+    #[darling(default)]
     only_generic_args: bool,
     #[darling(default)]
     rename: Option<Ident>,
+    /// Set when `generic_args` lists several instantiations; distinguishes this instantiation's
+    /// module from the others'.
+    #[darling(skip)]
+    instantiation: Option<String>,
+}
+
+/// The value of the `generic_args` option: either a single string or an array of strings, one
+/// per instantiation.
+#[derive(Clone, Debug, Default)]
+struct GenericArgsOpt(Vec<LitStr>);
+
+impl FromMeta for GenericArgsOpt {
+    fn from_value(value: &Lit) -> darling::Result<Self> {
+        LitStr::from_value(value).map(|lit| Self(vec![lit]))
+    }
+
+    fn from_expr(expr: &Expr) -> darling::Result<Self> {
+        match expr {
+            Expr::Array(array) => array
+                .elems
+                .iter()
+                .map(LitStr::from_expr)
+                .collect::<darling::Result<Vec<_>>>()
+                .map(Self),
+            Expr::Group(group) => Self::from_expr(&group.expr),
+            Expr::Lit(lit) => Self::from_value(&lit.lit),
+            _ => Err(darling::Error::unexpected_expr_type(expr)),
+        }
+        .map_err(|error| error.with_span(expr))
+    }
 }
 
 #[proc_macro_attribute]
 pub fn test_fuzz(args: TokenStream, item: TokenStream) -> TokenStream {
-    let attr_args =
-        NestedMeta::parse_meta_list(args.into()).expect("Could not parse attribute args");
-    let opts = TestFuzzOpts::from_list(&attr_args).expect("Could not parse `test_fuzz` options");
-
     let item = parse_macro_input!(item as ItemFn);
+    expand_test_fuzz(args.into(), &item)
+        .unwrap_or_else(|error| item_with_error(&item, error))
+        .into()
+}
+
+/// Emits `item` unmodified alongside `error`, so that uses of `item` do not produce additional
+/// errors.
+fn item_with_error(item: &impl ToTokens, error: syn::Error) -> TokenStream2 {
+    let error = error.into_compile_error();
+    quote! {
+        #item
+        #error
+    }
+}
+
+fn expand_test_fuzz(args: TokenStream2, item: &ItemFn) -> syn::Result<TokenStream2> {
+    let attr_args = NestedMeta::parse_meta_list(args)?;
+    let opts = TestFuzzOpts::from_list(&attr_args)?;
+
     let ItemFn {
         attrs,
         vis,
         modifiers,
         sig,
         block,
-    } = &item;
-    let (item, module) = map_method_or_fn(
+    } = item;
+    let (item, modules) = map_method_or_fn(
         &Generics::default(),
         None,
         None,
+        None,
         &opts,
         attrs,
         vis,
         modifiers,
         sig,
         block,
-    );
+    )?;
     let result = quote! {
         #item
-        #module
+        #(#modules)*
     };
     log(&result.to_token_stream());
-    result.into()
+    Ok(result)
+}
+
+#[allow(clippy::ptr_arg, clippy::too_many_arguments)]
+fn map_method_or_fn(
+    generics: &Generics,
+    trait_path: Option<&Path>,
+    self_ty: Option<&Type>,
+    sequence_mod_ident: Option<&Ident>,
+    opts: &TestFuzzOpts,
+    attrs: &Vec<Attribute>,
+    vis: &Visibility,
+    modifiers: &FnModifiers,
+    sig: &Signature,
+    block: &Block,
+) -> syn::Result<(TokenStream2, Vec<ItemMod>)> {
+    let instantiations = opts
+        .generic_args
+        .as_ref()
+        .map(|generic_args| generic_args.0.as_slice())
+        .unwrap_or_default();
+
+    if instantiations.len() <= 1 {
+        let (method, module, _) = map_instantiation(
+            generics,
+            trait_path,
+            self_ty,
+            sequence_mod_ident,
+            opts,
+            attrs,
+            vis,
+            modifiers,
+            sig,
+            block,
+            &TokenStream2::new(),
+        )?;
+        return Ok((method, vec![module]));
+    }
+
+    if opts.capture_ret {
+        return Err(syn::Error::new(
+            instantiations[1].span(),
+            "`capture_ret` cannot be used with multiple `generic_args`; consider listing only one \
+             instantiation",
+        ));
+    }
+
+    // smoelius: Each instantiation gets its own module. The method body must write the arguments
+    // for each of them, so the writes of all but the first instantiation are passed to the first.
+    let instantiation_opts = instantiations
+        .iter()
+        .map(|generic_args| TestFuzzOpts {
+            generic_args: Some(GenericArgsOpt(vec![generic_args.clone()])),
+            instantiation: Some(instantiation_suffix(generic_args)),
+            ..opts.clone()
+        })
+        .collect::<Vec<_>>();
+
+    let mut suffixes = BTreeMap::new();
+    for (generic_args, opts) in instantiations.iter().zip(&instantiation_opts) {
+        if let Some(other) = suffixes.insert(opts.instantiation.clone(), generic_args) {
+            return Err(syn::Error::new(
+                generic_args.span(),
+                format!(
+                    "`generic_args` instantiations `{}` and `{}` would have the same target name",
+                    other.value(),
+                    generic_args.value()
+                ),
+            ));
+        }
+    }
+
+    let mut modules = Vec::new();
+    let mut extra_writes = TokenStream2::new();
+    for opts in &instantiation_opts[1..] {
+        let (_, module, writes) = map_instantiation(
+            generics,
+            trait_path,
+            self_ty,
+            sequence_mod_ident,
+            opts,
+            attrs,
+            vis,
+            modifiers,
+            sig,
+            block,
+            &TokenStream2::new(),
+        )?;
+        modules.push(module);
+        extra_writes.extend(writes);
+    }
+
+    let (method, module, _) = map_instantiation(
+        generics,
+        trait_path,
+        self_ty,
+        sequence_mod_ident,
+        &instantiation_opts[0],
+        attrs,
+        vis,
+        modifiers,
+        sig,
+        block,
+        &extra_writes,
+    )?;
+    modules.insert(0, module);
+
+    Ok((method, modules))
 }
 
+/// Converts a `generic_args` string into something usable in an identifier, e.g., `Vec<u64>`
+/// becomes `Vec_u64`.
+fn instantiation_suffix(generic_args: &LitStr) -> String {
+    generic_args
+        .value()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+/// Expands a method or function for a single instantiation of `generic_args`. Returns the mapped
+/// method, its module, and the statements that write the instantiation's arguments.
+///
+/// `extra_writes` are additional statements to include in the mapped method, i.e., those of other
+/// instantiations.
 #[allow(
     clippy::ptr_arg,
     clippy::too_many_arguments,
@@ -235,17 +598,19 @@ pub fn test_fuzz(args: TokenStream, item: TokenStream) -> TokenStream {
     clippy::trivially_copy_pass_by_ref
 )]
 #[cfg_attr(dylint_lib = "supplementary", allow(commented_out_code))]
-fn map_method_or_fn(
+fn map_instantiation(
     generics: &Generics,
     trait_path: Option<&Path>,
     self_ty: Option<&Type>,
+    sequence_mod_ident: Option<&Ident>,
     opts: &TestFuzzOpts,
     attrs: &Vec<Attribute>,
     vis: &Visibility,
     modifiers: &FnModifiers,
     sig: &Signature,
     block: &Block,
-) -> (TokenStream2, ItemMod) {
+    extra_writes: &TokenStream2,
+) -> syn::Result<(TokenStream2, ItemMod, TokenStream2)> {
     let mut sig = sig.clone();
     let stmts = &block.stmts;
 
@@ -270,39 +635,118 @@ fn map_method_or_fn(
     };
 
     let mut conversions = Conversions::new();
-    opts.convert.iter().for_each(|s| {
-        let tokens = TokenStream::from_str(s).expect("Could not tokenize string");
-        let args = Parser::parse(Punctuated::<Type, token::Comma>::parse_terminated, tokens)
-            .expect("Could not parse `convert` argument");
-        assert!(args.len() == 2, "Could not parse `convert` argument");
-        let mut iter = args.into_iter();
-        let key = iter.next().expect("Should have two `convert` arguments");
-        let value = iter.next().expect("Should have two `convert` arguments");
+    let mut conversion_keys = Vec::new();
+    for lit in &opts.convert {
+        let args = parse_lit_str(
+            lit,
+            Punctuated::<Type, token::Comma>::parse_terminated,
+            "convert",
+        )?;
+        let Ok([key, value]) = <[Type; 2]>::try_from(args.into_iter().collect::<Vec<_>>()) else {
+            return Err(syn::Error::new(
+                lit.span(),
+                "`convert` argument should have the form \"X, Y\"",
+            ));
+        };
+        let mut key = key;
+        if !opts.no_std_conversions {
+            type_utils::expand_std_type_paths(&std_type_paths(), &mut key);
+        }
+        conversion_keys.push((OrdType(key.clone()), lit));
         conversions.insert(OrdType(key), (value, false));
-    });
+    }
 
     let opts_impl_generic_args = opts
         .impl_generic_args
-        .as_deref()
-        .map(parse_generic_arguments);
+        .as_ref()
+        .map(|lit| parse_generic_arguments(lit, "impl_generic_args"))
+        .transpose()?;
+
+    let opts_generic_args = opts
+        .generic_args
+        .as_ref()
+        .and_then(|generic_args| generic_args.0.first())
+        .map(|lit| parse_generic_arguments(lit, "generic_args"))
+        .transpose()?;
+
+    let globals = opts
+        .capture_globals
+        .as_ref()
+        .map(|lit| {
+            parse_lit_str(
+                lit,
+                Punctuated::<Path, token::Comma>::parse_terminated,
+                "capture_globals",
+            )
+        })
+        .transpose()?
+        .map(|globals| globals.into_iter().collect::<Vec<_>>())
+        .unwrap_or_default();
+    let global_idents = globals
+        .iter()
+        .map(|_| anonymous_ident())
+        .collect::<Vec<_>>();
+
+    if opts.capture_ret
+        && let Some(asyncness) = &sig.asyncness
+    {
+        return Err(syn::Error::new_spanned(
+            asyncness,
+            "`capture_ret` cannot be used with async functions",
+        ));
+    }
+
+    // smoelius: `capture_ret` wraps the target's body in a closure with the target's return type.
+    // Closures cannot return `impl Trait`.
+    if opts.capture_ret
+        && let ReturnType::Type(_, ty) = &sig.output
+        && let Some(impl_trait) = type_utils::find_impl_trait(ty)
+    {
+        return Err(syn::Error::new_spanned(
+            impl_trait,
+            "`capture_ret` cannot be used with functions that return `impl Trait`",
+        ));
+    }
 
-    let opts_generic_args = opts.generic_args.as_deref().map(parse_generic_arguments);
+    for input in &sig.inputs {
+        if let FnArg::Typed(PatType { pat, .. }) = input
+            && pat_utils::pat_idents(pat).len() >= 2
+        {
+            return Err(syn::Error::new_spanned(
+                pat,
+                "`test_fuzz` does not support patterns that bind multiple identifiers; consider \
+                 binding the argument to a single identifier and destructuring it in the body",
+            ));
+        }
+    }
+
+    // smoelius: There is no return value to record if no corpus files are written.
+    let capture_ret = opts.capture_ret && !opts.only_generic_args;
 
     // smoelius: Error early.
     #[cfg(fuzzing)]
     if !opts.only_generic_args {
         if is_generic(generics) && opts_impl_generic_args.is_none() {
-            panic!(
-                "`{}` appears in a generic impl but `impl_generic_args` was not specified",
-                sig.ident.to_string(),
-            );
+            return Err(syn::Error::new_spanned(
+                &sig.ident,
+                format!(
+                    "`{}` appears in a generic impl but `impl_generic_args` was not specified; \
+                     run `cargo test` and then `cargo test-fuzz --suggest-generic-args` for \
+                     suggestions",
+                    sig.ident,
+                ),
+            ));
         }
 
         if is_generic(&sig.generics) && opts_generic_args.is_none() {
-            panic!(
-                "`{}` is generic but `generic_args` was not specified",
-                sig.ident.to_string(),
-            );
+            return Err(syn::Error::new_spanned(
+                &sig.generics,
+                format!(
+                    "`{}` is generic but `generic_args` was not specified; run `cargo test` and \
+                     then `cargo test-fuzz --suggest-generic-args` for suggestions",
+                    sig.ident,
+                ),
+            ));
         }
     }
 
@@ -337,17 +781,20 @@ fn map_method_or_fn(
     let (impl_generics, ty_generics, where_clause) = combined_generics.split_for_impl();
     let (impl_generics_deserializable, _, _) = combined_generics_deserializable.split_for_impl();
 
-    let args_where_clause: Option<WhereClause> = opts.bounds.as_ref().map(|bounds| {
-        let tokens = TokenStream::from_str(bounds).expect("Could not tokenize string");
-        let where_predicates = Parser::parse(
-            Punctuated::<WherePredicate, token::Comma>::parse_terminated,
-            tokens,
-        )
-        .expect("Could not parse type bounds");
-        parse_quote! {
-            where #where_predicates
-        }
-    });
+    let args_where_clause: Option<WhereClause> = opts
+        .bounds
+        .as_ref()
+        .map(|bounds| {
+            let where_predicates = parse_lit_str(
+                bounds,
+                Punctuated::<WherePredicate, token::Comma>::parse_terminated,
+                "bounds",
+            )?;
+            Ok::<_, syn::Error>(parse_quote! {
+                where #where_predicates
+            })
+        })
+        .transpose()?;
 
     // smoelius: "Constraints don’t count as 'using' a type parameter," as explained by Daniel Keep
     // here: https://users.rust-lang.org/t/error-parameter-t-is-never-used-e0392-but-i-use-it/5673
@@ -368,7 +815,7 @@ fn map_method_or_fn(
     let generic_args = opts_generic_args.as_ref().map(args_as_turbofish);
     let combined_generic_args_base = combine_options(
         opts_impl_generic_args.clone(),
-        opts_generic_args,
+        opts_generic_args.clone(),
         |mut left, right| {
             left.extend(right);
             left
@@ -386,10 +833,22 @@ fn map_method_or_fn(
             .iter()
             .filter(|arg| matches!(arg, GenericArgument::Lifetime(..)))
             .count();
-        #[allow(clippy::cast_possible_wrap)]
-        let n_missing_lifetime_args =
-            usize::try_from(n_lifetime_params as isize - n_lifetime_args as isize)
-                .expect("n_lifetime_params < n_lifetime_args");
+        let Some(n_missing_lifetime_args) = n_lifetime_params.checked_sub(n_lifetime_args) else {
+            let span = opts
+                .generic_args
+                .as_ref()
+                .and_then(|generic_args| generic_args.0.first())
+                .or(opts.impl_generic_args.as_ref())
+                .map_or_else(|| sig.ident.span(), LitStr::span);
+            return Err(syn::Error::new(
+                span,
+                format!(
+                    "{n_lifetime_args} lifetime arguments were given, but `{}` has only \
+                     {n_lifetime_params} lifetime parameters",
+                    sig.ident
+                ),
+            ));
+        };
         let dummy_lifetime = GenericArgument::Lifetime(parse_quote! { 'static });
         args.extend(std::iter::repeat_n(dummy_lifetime, n_missing_lifetime_args));
         args_as_turbofish(&args)
@@ -397,26 +856,103 @@ fn map_method_or_fn(
 
     let self_ty_base = self_ty.and_then(type_utils::type_base);
 
+    let mod_ident = mod_ident(opts, self_ty_base, &sig.ident);
+
+    let dyn_impls = opts
+        .dyn_impls
+        .iter()
+        .map(parse_dyn_impls)
+        .collect::<syn::Result<Vec<_>>>()?;
+    let dyn_enum_idents = dyn_impls
+        .iter()
+        .map(|(ty, _)| {
+            type_utils::type_path_ident("Dyn", ty).expect("Could not determine trait name")
+        })
+        .collect::<Vec<_>>();
+    let mut dyn_enum_paths = DynImpls::new();
+    for (((ty, _), enum_ident), lit) in dyn_impls.iter().zip(&dyn_enum_idents).zip(&opts.dyn_impls)
+    {
+        let path = parse_quote! { #mod_ident :: #enum_ident };
+        if dyn_enum_paths
+            .insert(OrdType(ty.clone()), (path, false))
+            .is_some()
+        {
+            return Err(syn::Error::new(
+                lit.span(),
+                format!(
+                    "`{}` appears in multiple `dyn_impls` arguments; consider combining them",
+                    ty.to_token_stream()
+                ),
+            ));
+        }
+    }
+
+    // smoelius: The expanded types are used only to generate the `Args` struct. The original types
+    // are restored afterwards so that the target's `use` declarations remain used.
+    let original_input_tys = if opts.no_std_conversions {
+        Vec::new()
+    } else {
+        let std_type_paths = std_type_paths();
+        sig.inputs
+            .iter_mut()
+            .map(|input| {
+                let FnArg::Typed(PatType { ty, .. }) = input else {
+                    return None;
+                };
+                let original_ty = ty.clone();
+                type_utils::expand_std_type_paths(&std_type_paths, ty);
+                Some(original_ty)
+            })
+            .collect()
+    };
+
     let (mut arg_attrs, mut arg_idents, mut arg_tys, fmt_args, mut ser_args, de_args) = {
         let mut candidates = BTreeSet::new();
+        let preserve_sharing =
+            preserves_sharing(&combined_type_idents, trait_path, self_ty, &sig.inputs);
         let result = map_args(
             &mut conversions,
+            &mut dyn_enum_paths,
             &mut candidates,
+            Some(combined_type_idents.as_slice()).filter(|_| preserve_sharing),
             trait_path,
             self_ty,
             sig.inputs.iter_mut(),
         );
-        for (from, (to, used)) in conversions {
-            assert!(
-                used,
-                r#"Conversion "{}" -> "{}" does not apply to the following candidates: {:#?}"#,
-                from,
-                OrdType(to),
-                candidates
-            );
+        for (input, original_ty) in sig.inputs.iter_mut().zip(original_input_tys) {
+            if let (FnArg::Typed(PatType { ty, .. }), Some(original_ty)) = (input, original_ty) {
+                *ty = original_ty;
+            }
+        }
+        for (from, lit) in conversion_keys {
+            let (to, used) = &conversions[&from];
+            if !used {
+                return Err(syn::Error::new(
+                    lit.span(),
+                    format!(
+                        r#"Conversion "{}" -> "{}" does not apply to the following candidates: {:#?}"#,
+                        from,
+                        OrdType(to.clone()),
+                        candidates
+                    ),
+                ));
+            }
+        }
+        for ((ty, _), lit) in dyn_impls.iter().zip(&opts.dyn_impls) {
+            let (_, used) = &dyn_enum_paths[&OrdType(ty.clone())];
+            if !used {
+                return Err(syn::Error::new(
+                    lit.span(),
+                    format!(
+                        r#"`dyn_impls` for "{}" does not apply to any argument; it applies to arguments of type `&{0}`, `&mut {0}`, and `Box<{0}>`"#,
+                        OrdType(ty.clone())
+                    ),
+                ));
+            }
         }
         result
     };
+    let n_inputs = arg_idents.len();
     arg_attrs.extend(phantom_idents.iter().map(|_| Attrs::new()));
     arg_idents.extend_from_slice(&phantom_idents);
     arg_tys.extend_from_slice(&phantom_tys);
@@ -461,7 +997,6 @@ fn map_method_or_fn(
     };
 
     let target_ident = &sig.ident;
-    let mod_ident = mod_ident(opts, self_ty_base, target_ident);
 
     // smoelius: This is a hack. When `only_generic_args` is specified, the user should not have
     // to also specify trait bounds. But `Args` is used to get the module path at runtime via
@@ -501,38 +1036,90 @@ fn map_method_or_fn(
         test_fuzz::runtime::write_impl_generic_args::< #mod_ident :: Args #ty_generics_as_turbofish>(&impl_generic_args);
         test_fuzz::runtime::write_generic_args::< #mod_ident :: Args #ty_generics_as_turbofish>(&generic_args);
     };
+    let corpus_file_ident = anonymous_ident();
     let write_args = if opts.only_generic_args {
         quote! {}
     } else {
-        quote! {
+        let write_args: Expr = parse_quote! {
             #mod_ident :: write_args::< #(#combined_type_idents),* >(#mod_ident :: Args {
                 #(#ser_args),*
-            });
+            })
+        };
+        if capture_ret {
+            quote! {
+                #corpus_file_ident = Some(#write_args);
+            }
+        } else {
+            quote! {
+                #write_args;
+            }
+        }
+    };
+    // smoelius: When there are multiple instantiations, only write the arguments for the one
+    // matching the method's actual generic arguments.
+    let instantiation_guard = opts.instantiation.as_ref().map(|_| {
+        let generic_arg_tys = opts_generic_args.iter().flatten().filter_map(|arg| {
+            if let GenericArgument::Type(ty) = arg {
+                Some(ty)
+            } else {
+                None
+            }
+        });
+        quote! {
+            && [#(#ty_names),*] == [#(std::any::type_name::< #generic_arg_tys >()),*]
+        }
+    });
+    // smoelius: Capturing is keyed on a runtime check rather than on `cfg(test)`, so that calls
+    // made from integration tests and binaries can be captured too. See `capture_hook!`.
+    let capture_condition = if opts.enable_in_production {
+        quote! {
+            (test_fuzz::runtime::capturing()
+                || (test_fuzz::runtime::write_enabled()
+                    && !test_fuzz::runtime::test_fuzz_enabled()))
+        }
+    } else {
+        quote! {
+            test_fuzz::runtime::capturing()
         }
     };
     let write_generic_args_and_args = quote! {
-        #[cfg(test)]
-        if !test_fuzz::runtime::test_fuzz_enabled() {
+        if #capture_condition #instantiation_guard {
             #write_generic_args
             #write_args
         }
     };
-    let (in_production_write_generic_args_and_args, mod_attr) = if opts.enable_in_production {
+    let (corpus_file_decl, body) = if capture_ret {
+        let ret_ident = anonymous_ident();
+        let closure_ident = anonymous_ident();
+        let closure_ret_ty = match &sig.output {
+            ReturnType::Type(_, ty) => quote! { -> #ty },
+            ReturnType::Default => quote! {},
+        };
+        let outcome = outcome(&ret_ident);
         (
             quote! {
-                #[cfg(not(test))]
-                if test_fuzz::runtime::write_enabled() {
-                    #write_generic_args
-                    #write_args
+                #[allow(clippy::useless_let_if_seq)]
+                let mut #corpus_file_ident: Option<std::path::PathBuf> = None;
+            },
+            quote! {
+                let #closure_ident = || #closure_ret_ty { #(#stmts)* };
+                if let Some(corpus_file) = &#corpus_file_ident {
+                    let #ret_ident = std::panic::catch_unwind(std::panic::AssertUnwindSafe(#closure_ident));
+                    test_fuzz::runtime::write_ret::< #mod_ident :: Args #ty_generics_as_turbofish>(corpus_file, &#outcome);
+                    match #ret_ident {
+                        Ok(ret) => ret,
+                        Err(payload) => std::panic::resume_unwind(payload),
+                    }
+                } else {
+                    #closure_ident()
                 }
             },
-            quote! {},
         )
     } else {
         (
             quote! {},
             quote! {
-                #[cfg(test)]
+                #(#stmts)*
             },
         )
     };
@@ -554,6 +1141,19 @@ fn map_method_or_fn(
             let mut args = UsingReader::<_>::read_args #combined_generic_args (std::io::stdin());
         }
     };
+    // smoelius: When replaying, displaying, or generating coverage, the input is read from
+    // `reader`, which `test_fuzz::runtime::replay` may point at a file rather than standard
+    // input.
+    let input_args_from_reader = {
+        #[cfg(feature = "__persistent")]
+        quote! {
+            let _ = reader;
+        }
+        #[cfg(not(feature = "__persistent"))]
+        quote! {
+            let mut args = UsingReader::<_>::read_args #combined_generic_args (reader);
+        }
+    };
     let output_args = {
         #[cfg(feature = "__persistent")]
         quote! {}
@@ -572,9 +1172,16 @@ fn map_method_or_fn(
     let args_ret_ty: Type = parse_quote! {
         <Args #combined_generic_args_with_dummy_lifetimes as HasRetTy>::RetTy
     };
-    let call: Expr = if let Some(self_ty) = self_ty {
+    let callee = if let Some(self_ty) = self_ty {
         let opts_impl_generic_args = opts_impl_generic_args.unwrap_or_default();
-        let map = generic_params_map(generics, &opts_impl_generic_args);
+        let map = generic_params_map(generics, &opts_impl_generic_args).map_err(|message| {
+            syn::Error::new(
+                opts.impl_generic_args
+                    .as_ref()
+                    .map_or_else(|| sig.ident.span(), LitStr::span),
+                message,
+            )
+        })?;
         let self_ty_with_generic_args =
             type_utils::type_as_turbofish(&type_utils::map_type_generic_params(&map, self_ty));
         let qualified_self = if let Some(trait_path) = trait_path {
@@ -584,28 +1191,143 @@ fn map_method_or_fn(
             quote! {
                 < #self_ty_with_generic_args as #trait_path_with_generic_args >
             }
+        } else if matches!(self_ty, Type::TraitObject(_)) {
+            quote! {
+                < #self_ty_with_generic_args >
+            }
         } else {
             self_ty_with_generic_args
         };
-        parse_quote! {
-            #qualified_self :: #target_ident #generic_args (
-                #(#de_args),*
-            )
+        quote! {
+            #qualified_self :: #target_ident #generic_args
         }
     } else {
-        parse_quote! {
-            super :: #target_ident #generic_args (
+        quote! {
+            super :: #target_ident #generic_args
+        }
+    };
+    let call: Expr = parse_quote! {
+        #callee (
+            #(#de_args),*
+        )
+    };
+    let execute_with = opts
+        .execute_with
+        .as_ref()
+        .map(|lit| parse_lit_str(lit, Expr::parse, "execute_with"))
+        .transpose()?;
+    let in_environment = |call: Expr| -> Expr {
+        if let Some(execute_with) = &execute_with {
+            parse_quote! {
+                #execute_with (|| #call)
+            }
+        } else {
+            call
+        }
+    };
+    // smoelius: When the target's calls are recorded as part of a sequence, the receiver is the
+    // first argument. It is excluded from `CallArgs`, because the sequence's receiver is used in
+    // its place.
+    let (record_call, call_args_items) = if let Some(sequence_mod_ident) = sequence_mod_ident {
+        let call_arg_ident_tys = &attr_pub_arg_ident_tys[1..n_inputs];
+        let call_fmt_args = &fmt_args[1..];
+        let call_ser_args = &ser_args[1..n_inputs];
+        let call_de_args = &de_args[1..];
+        let call_in_environment = in_environment(parse_quote! {
+            #callee (
+                receiver,
+                #(#call_de_args),*
+            )
+        });
+        let name = target_ident.to_string();
+        (
+            quote! {
+                if test_fuzz::runtime::capturing() {
+                    #sequence_mod_ident :: record(
+                        self,
+                        #sequence_mod_ident :: Call :: #target_ident(#mod_ident :: CallArgs {
+                            #(#call_ser_args),*
+                        }),
+                    );
+                }
+            },
+            quote! {
+                #[derive(serde::Deserialize, serde::Serialize)]
+                pub(super) struct CallArgs {
+                    #(#call_arg_ident_tys),*
+                }
+
+                impl CallArgs {
+                    pub(super) fn call(self, receiver: &mut #self_ty) {
+                        #[allow(unused_mut, unused_variables)]
+                        let mut args = self;
+                        let _ = #call_in_environment;
+                    }
+                }
+
+                impl std::fmt::Debug for CallArgs {
+                    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        use test_fuzz::runtime::TryDebugFallback;
+                        let mut debug_struct = fmt.debug_struct(#name);
+                        #(#call_fmt_args)*
+                        debug_struct.finish()
+                    }
+                }
+            },
+        )
+    } else {
+        (quote! {}, quote! {})
+    };
+    let call_in_environment = if let Some(lit) = &opts.differential {
+        let reference = parse_lit_str(lit, Path::parse, "differential")?;
+        let reference_call = in_environment(parse_quote! {
+            #reference (
                 #(#de_args),*
             )
+        });
+        let call_in_environment = in_environment(call);
+        let message = format!(
+            "`{}` and `{}` returned different results",
+            target_ident,
+            reference.to_token_stream().to_string().replace(' ', "")
+        );
+        // smoelius: The reference implementation is called first, on a clone of the arguments, in
+        // case the target mutates them.
+        parse_quote! {
+            {
+                let reference_ret = {
+                    let mut args = Clone::clone(&args);
+                    #reference_call
+                };
+                let ret = #call_in_environment;
+                assert_eq!(ret, reference_ret, #message);
+                ret
+            }
         }
+    } else {
+        in_environment(call)
     };
-    let call_in_environment = if let Some(s) = &opts.execute_with {
-        let execute_with: Expr = parse_str(s).expect("Could not parse `execute_with` argument");
+    let call_in_environment = if let Some(lit) = &opts.check {
+        let check = parse_lit_str(lit, Path::parse, "check")?;
+        let check_str = check.to_token_stream().to_string().replace(' ', "");
+        // smoelius: Pass the arguments as a tuple of references, so that `check` need not name the
+        // generated `Args` type.
+        let input_idents = &arg_idents[..n_inputs];
         parse_quote! {
-            #execute_with (|| #call)
+            {
+                let args_before = Clone::clone(&args);
+                let ret = #call_in_environment;
+                assert!(
+                    #check((#(&args_before.#input_idents,)*), &ret),
+                    "`{}` does not hold for {:?}",
+                    #check_str,
+                    args_before
+                );
+                ret
+            }
         }
     } else {
-        call
+        call_in_environment
     };
     let call_in_environment_with_deserialized_arguments = {
         #[cfg(feature = "__persistent")]
@@ -654,6 +1376,83 @@ fn map_method_or_fn(
             eprintln!();
         }
     };
+    let check_ret = if capture_ret && cfg!(not(feature = "__persistent")) {
+        let ret_ident = anonymous_ident();
+        let outcome = outcome(&ret_ident);
+        quote! {
+            if test_fuzz::runtime::replay_enabled() {
+                if let Some(expected) = test_fuzz::runtime::expected_ret() {
+                    let ret = args.map(|mut args|
+                        std::panic::catch_unwind(std::panic::AssertUnwindSafe(||
+                            #call_in_environment
+                        ))
+                    );
+                    // smoelius: Arguments that no longer deserialize are a regression too.
+                    if let Some(#ret_ident) = ret {
+                        test_fuzz::runtime::check_ret(&expected, &#outcome);
+                    } else {
+                        test_fuzz::runtime::check_ret(
+                            &expected,
+                            test_fuzz::runtime::UNDESERIALIZABLE_ARGS,
+                        );
+                    }
+                    return;
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
+    let (write_args_and_globals, read_args_and_globals) = if globals.is_empty() {
+        (
+            quote! {
+                test_fuzz::runtime::write_args(&args)
+            },
+            quote! {
+                let args = test_fuzz::runtime::read_args::<Args #ty_generics_as_turbofish, _>(reader);
+                args.map(|Args { #(#arg_idents),* }| #mod_ident :: Args {
+                    #(#arg_idents),*
+                })
+            },
+        )
+    } else {
+        // smoelius: The globals' types are not known to the macro. So they are left to be
+        // inferred, from `Global::capture`'s return type when writing, and from
+        // `Global::restore`'s argument type when reading.
+        (
+            quote! {
+                let globals = ( #(test_fuzz::Global::capture(& #globals),)* );
+                test_fuzz::runtime::write_args_and_globals(&args, &globals)
+            },
+            quote! {
+                let args = test_fuzz::runtime::read_args::<(Args #ty_generics_as_turbofish, _), _>(reader);
+                args.map(|(Args { #(#arg_idents),* }, ( #(#global_idents,)* ))| {
+                    #(test_fuzz::Global::restore(& #globals, #global_idents);)*
+                    #mod_ident :: Args {
+                        #(#arg_idents),*
+                    }
+                })
+            },
+        )
+    };
+    let clone_impl = if opts.check.is_some() || opts.differential.is_some() {
+        quote! {
+            impl #impl_generics Clone for Args #ty_generics #where_clause {
+                fn clone(&self) -> Self {
+                    Self {
+                        #(#arg_idents: Clone::clone(&self.#arg_idents)),*
+                    }
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
+    let dyn_enum_items = dyn_impls
+        .iter()
+        .zip(&dyn_enum_idents)
+        .map(|((ty, impls), enum_ident)| dyn_enum_items(ty, impls, enum_ident))
+        .collect::<Vec<_>>();
     let mod_items = if opts.only_generic_args {
         quote! {}
     } else {
@@ -661,7 +1460,7 @@ fn map_method_or_fn(
             // smoelius: It is tempting to want to put all of these functions under `impl Args`.
             // But `write_args` and `read args` impose different bounds on their arguments. So
             // I don't think that idea would work.
-            pub(super) fn write_args #impl_generics (Args { #(#arg_idents),* }: Args #ty_generics_as_turbofish) #where_clause {
+            pub(super) fn write_args #impl_generics (Args { #(#arg_idents),* }: Args #ty_generics_as_turbofish) -> std::path::PathBuf #where_clause {
                 #[derive(serde::Serialize)]
                 struct Args #ty_generics #args_where_clause {
                     #(#attr_pub_arg_ident_tys),*
@@ -669,7 +1468,7 @@ fn map_method_or_fn(
                 let args = Args {
                     #(#arg_idents),*
                 };
-                test_fuzz::runtime::write_args(&args);
+                #write_args_and_globals
             }
 
             struct UsingReader<R>(R);
@@ -680,13 +1479,12 @@ fn map_method_or_fn(
                     struct Args #ty_generics #args_where_clause {
                         #(#attr_pub_arg_ident_tys),*
                     }
-                    let args = test_fuzz::runtime::read_args::<Args #ty_generics_as_turbofish, _>(reader);
-                    args.map(|Args { #(#arg_idents),* }| #mod_ident :: Args {
-                        #(#arg_idents),*
-                    })
+                    #read_args_and_globals
                 }
             }
 
+            #clone_impl
+
             impl #impl_generics std::fmt::Debug for Args #ty_generics #where_clause {
                 fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
                     use test_fuzz::runtime::TryDebugFallback;
@@ -745,18 +1543,21 @@ fn map_method_or_fn(
                                 if test_fuzz::runtime::coverage_enabled() {
                                     #warn_if_function_is_nontrivial
                                 }
-                                #input_args
-                                if test_fuzz::runtime::display_enabled() {
-                                    #output_args
-                                }
-                                if test_fuzz::runtime::coverage_enabled()
-                                    || test_fuzz::runtime::replay_enabled()
-                                {
-                                    #call_in_environment_with_deserialized_arguments
-                                    if test_fuzz::runtime::replay_enabled() {
-                                        #output_ret
+                                test_fuzz::runtime::replay(|reader| {
+                                    #input_args_from_reader
+                                    if test_fuzz::runtime::display_enabled() {
+                                        #output_args
                                     }
-                                }
+                                    if test_fuzz::runtime::coverage_enabled()
+                                        || test_fuzz::runtime::replay_enabled()
+                                    {
+                                        #check_ret
+                                        #call_in_environment_with_deserialized_arguments
+                                        if test_fuzz::runtime::replay_enabled() {
+                                            #output_ret
+                                        }
+                                    }
+                                });
                             } else {
                                 std::panic::set_hook(std::boxed::Box::new(|_| std::process::abort()));
                                 #input_args
@@ -775,71 +1576,385 @@ fn map_method_or_fn(
         )
     };
     let defaultness = modifiers.defaultness;
-    (
+    Ok((
         parse_quote! {
             #(#attrs)* #vis #defaultness #sig {
                 #maybe_use_cast_checks
 
+                #corpus_file_decl
+
                 #write_generic_args_and_args
 
-                #in_production_write_generic_args_and_args
+                #extra_writes
 
-                #(#stmts)*
+                #record_call
+
+                #body
             }
         },
         parse_quote! {
-            #mod_attr
             mod #mod_ident {
                 use super::*;
 
+                // smoelius: A package's own tests always capture.
+                #[cfg(test)]
+                test_fuzz::runtime::inventory::submit! {
+                    test_fuzz::runtime::CaptureHook
+                }
+
                 #struct_args
 
+                #(#dyn_enum_items)*
+
                 #mod_items
 
+                #call_args_items
+
                 #generic_args_dependent_mod_items
 
                 #[test]
                 fn entry() {
+                    if test_fuzz::runtime::location_enabled() {
+                        test_fuzz::runtime::print_location(file!(), line!());
+                        return;
+                    }
+
                     #entry_stmts
                 }
             }
         },
-    )
+        write_generic_args_and_args,
+    ))
+}
+
+/// Generates an enum with one variant for each type in `impls`, along with functions to convert
+/// between the enum and the trait object type `ty`.
+fn dyn_enum_items(ty: &Type, impls: &[Type], enum_ident: &Ident) -> TokenStream2 {
+    let variant_idents = impls
+        .iter()
+        .map(|ty| {
+            type_utils::type_path_ident("Impl", ty).expect("Could not determine implementor name")
+        })
+        .collect::<Vec<_>>();
+    let ty_str = ty.to_token_stream().to_string();
+    let impls_str = impls
+        .iter()
+        .map(|ty| ty.to_token_stream().to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    quote! {
+        #[allow(non_camel_case_types)]
+        #[derive(Clone, serde::Deserialize, serde::Serialize)]
+        pub(super) enum #enum_ident {
+            #(#variant_idents(#impls)),*
+        }
+
+        #[allow(dead_code)]
+        impl #enum_ident {
+            pub(super) fn from_dyn(value: &#ty) -> Self {
+                let any: &dyn std::any::Any = value;
+                #(
+                    if let Some(value) = any.downcast_ref::<#impls>() {
+                        return Self::#variant_idents(value.clone());
+                    }
+                )*
+                panic!("`{}` value is not one of `{}`", #ty_str, #impls_str);
+            }
+
+            pub(super) fn as_dyn(&self) -> &#ty {
+                match self {
+                    #(Self::#variant_idents(value) => value),*
+                }
+            }
+
+            pub(super) fn as_mut_dyn(&mut self) -> &mut #ty {
+                match self {
+                    #(Self::#variant_idents(value) => value),*
+                }
+            }
+
+            pub(super) fn into_box(self) -> Box<#ty> {
+                match self {
+                    #(Self::#variant_idents(value) => Box::new(value)),*
+                }
+            }
+        }
+
+        impl std::fmt::Debug for #enum_ident {
+            fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                use test_fuzz::runtime::TryDebugFallback;
+                match self {
+                    #(Self::#variant_idents(value) => {
+                        test_fuzz::runtime::TryDebug(value)
+                            .apply(&mut |value| std::fmt::Debug::fmt(value, fmt))
+                    })*
+                }
+            }
+        }
+    }
+}
+
+/// Determines whether calls to the method with signature `sig` can be recorded as part of a
+/// sequence, i.e., whether the method takes `&self` or `&mut self` and is not generic.
+fn is_sequenceable(sig: &Signature) -> bool {
+    sig.receiver()
+        .is_some_and(|receiver| matches!(receiver.kind, ReceiverKind::Reference(..)))
+        && sig.generics.params.is_empty()
+        && sig.asyncness.is_none()
+}
+
+#[allow(clippy::too_many_lines)]
+fn sequence_module(
+    self_ty: &Type,
+    constructor: &LitStr,
+    sequence_id: &LitStr,
+    sequence_mod_ident: &Ident,
+    sequenced: &[Sequenced],
+) -> syn::Result<ItemMod> {
+    if sequenced.is_empty() {
+        return Err(syn::Error::new(
+            constructor.span(),
+            "`sequence` requires a `test_fuzz` method that takes `&self` or `&mut self`; consider \
+             adding `#[test_fuzz]` to such a method",
+        ));
+    }
+
+    let constructor = parse_lit_str(constructor, Path::parse, "sequence")?;
+    let constructor = if let Some(ident) = constructor.get_ident() {
+        quote! { < #self_ty > :: #ident }
+    } else {
+        quote! { #constructor }
+    };
+
+    let sequence_id = parse_lit_str(sequence_id, Ident::parse, "sequence_id")?;
+
+    let (method_idents, method_mod_idents): (Vec<_>, Vec<_>) = sequenced.iter().cloned().unzip();
+
+    let input_calls = {
+        #[cfg(feature = "__persistent")]
+        quote! {}
+        #[cfg(not(feature = "__persistent"))]
+        quote! {
+            let calls = test_fuzz::runtime::read_args::<Vec<Call>, _>(std::io::stdin());
+        }
+    };
+    let input_calls_from_reader = {
+        #[cfg(feature = "__persistent")]
+        quote! {
+            let _ = reader;
+        }
+        #[cfg(not(feature = "__persistent"))]
+        quote! {
+            let calls = test_fuzz::runtime::read_args::<Vec<Call>, _>(reader);
+        }
+    };
+    let call_sequence_with_deserialized_calls = {
+        #[cfg(feature = "__persistent")]
+        quote! {
+            test_fuzz::afl::fuzz!(|data: &[u8]| {
+                if let Some(calls) = test_fuzz::runtime::read_args::<Vec<Call>, _>(data) {
+                    call_sequence(calls);
+                }
+            });
+        }
+        #[cfg(not(feature = "__persistent"))]
+        quote! {
+            if let Some(calls) = calls {
+                call_sequence(calls);
+            }
+        }
+    };
+    let output_calls = {
+        #[cfg(feature = "__persistent")]
+        quote! {}
+        #[cfg(not(feature = "__persistent"))]
+        quote! {
+            calls.as_ref().map(|x| {
+                if test_fuzz::runtime::pretty_print_enabled() {
+                    eprint!("{:#?}", x);
+                } else {
+                    eprint!("{:?}", x);
+                };
+            });
+            eprintln!();
+        }
+    };
+
+    Ok(parse_quote! {
+        mod #sequence_mod_ident {
+            use super::*;
+
+            #[allow(non_camel_case_types)]
+            #[derive(serde::Deserialize, serde::Serialize)]
+            pub(super) enum Call {
+                #(#method_idents(#method_mod_idents :: CallArgs)),*
+            }
+
+            impl std::fmt::Debug for Call {
+                fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    match self {
+                        #(Self::#method_idents(args) => std::fmt::Debug::fmt(args, fmt)),*
+                    }
+                }
+            }
+
+            thread_local! {
+                static LOG: std::cell::RefCell<test_fuzz::runtime::SequenceLog<Call>> =
+                    std::cell::RefCell::new(test_fuzz::runtime::SequenceLog::default());
+            }
+
+            // smoelius: The hooks may be called while the thread is exiting or while the log is
+            // borrowed, e.g., if a receiver is dropped during a call's serialization. In either
+            // case, the call to the hook is ignored.
+            static HOOKS: test_fuzz::runtime::SequenceHooks = test_fuzz::runtime::SequenceHooks {
+                fork,
+                end,
+            };
+
+            fn fork(from: u64, to: u64) {
+                let _ = LOG.try_with(|log| {
+                    if let Ok(mut log) = log.try_borrow_mut() {
+                        log.fork(from, to);
+                    }
+                });
+            }
+
+            fn end(id: u64) {
+                let _ = LOG.try_with(|log| {
+                    if let Ok(mut log) = log.try_borrow_mut() {
+                        log.end(id);
+                    }
+                });
+            }
+
+            pub(super) fn record(receiver: &#self_ty, call: Call) {
+                let sequence_id: &test_fuzz::SequenceId = &receiver.#sequence_id;
+                sequence_id.set_hooks(&HOOKS);
+                LOG.with_borrow_mut(|log| log.record(sequence_id.id(), call));
+            }
+
+            fn call_sequence(calls: Vec<Call>) {
+                let mut receiver: #self_ty = #constructor();
+                for call in calls {
+                    match call {
+                        #(Call::#method_idents(args) => args.call(&mut receiver)),*
+                    }
+                }
+            }
+
+            #[test]
+            fn entry() {
+                if test_fuzz::runtime::location_enabled() {
+                    test_fuzz::runtime::print_location(file!(), line!());
+                    return;
+                }
+
+                test_fuzz::runtime::warn_if_test_fuzz_not_enabled();
+
+                // smoelius: Do not set the panic hook when replaying. Leave cargo test's panic hook
+                // in place.
+                if test_fuzz::runtime::test_fuzz_enabled() {
+                    if test_fuzz::runtime::coverage_enabled()
+                        || test_fuzz::runtime::display_enabled()
+                        || test_fuzz::runtime::replay_enabled()
+                    {
+                        test_fuzz::runtime::replay(|reader| {
+                            #input_calls_from_reader
+                            if test_fuzz::runtime::display_enabled() {
+                                #output_calls
+                            }
+                            if test_fuzz::runtime::coverage_enabled()
+                                || test_fuzz::runtime::replay_enabled()
+                            {
+                                #call_sequence_with_deserialized_calls
+                            }
+                        });
+                    } else {
+                        std::panic::set_hook(std::boxed::Box::new(|_| std::process::abort()));
+                        #input_calls
+                        #call_sequence_with_deserialized_calls
+                        let _ = std::panic::take_hook();
+                    }
+                }
+            }
+        }
+    })
 }
 
 fn generic_params_map<'a, 'b>(
     generics: &'a Generics,
     impl_generic_args: &'b Punctuated<GenericArgument, token::Comma>,
-) -> BTreeMap<&'a Ident, &'b GenericArgument> {
+) -> Result<BTreeMap<&'a Ident, &'b GenericArgument>, String> {
     let n = generics
         .params
         .len()
         .checked_sub(impl_generic_args.len())
-        .unwrap_or_else(|| {
-            panic!(
-                "{:?} is shorter than {:?}",
-                generics.params, impl_generic_args
+        .ok_or_else(|| {
+            format!(
+                "`impl_generic_args` has {} arguments, but the `impl` has only {} parameters",
+                impl_generic_args.len(),
+                generics.params.len()
+            )
+        })?;
+    let mut map = BTreeMap::new();
+    for (key, value) in generics.params.iter().skip(n).zip(impl_generic_args) {
+        if let GenericParam::Type(TypeParam { ident, .. }) = key {
+            if !matches!(value, GenericArgument::Type(_)) {
+                return Err(format!(
+                    "`{}` is not a type, but is given for type parameter `{ident}`",
+                    value.to_token_stream()
+                ));
+            }
+            map.insert(ident, value);
+        }
+    }
+    Ok(map)
+}
+
+/// Determines whether `Rc` and `Arc` arguments should be serialized in a way that preserves
+/// sharing. Doing so changes the format of the serialized arguments, so it is done only when at
+/// least two arguments are shareable pointers, i.e., only when sharing could occur.
+fn preserves_sharing<'a>(
+    type_idents: &[Ident],
+    trait_path: Option<&Path>,
+    self_ty: Option<&Type>,
+    inputs: impl IntoIterator<Item = &'a FnArg>,
+) -> bool {
+    let n_shareable_pointers = inputs
+        .into_iter()
+        .filter(|input| {
+            let FnArg::Typed(PatType { ty, .. }) = input else {
+                return false;
+            };
+            let ty = self_ty.map_or_else(
+                || *ty.clone(),
+                |self_ty| type_utils::expand_self(trait_path, self_ty, ty),
             );
-        });
-    generics
-        .params
-        .iter()
-        .skip(n)
-        .zip(impl_generic_args)
-        .filter_map(|(key, value)| {
-            if let GenericParam::Type(TypeParam { ident, .. }) = key {
-                Some((ident, value))
+            let ty = if let Type::Reference(TypeReference { elem, .. }) = ty {
+                *elem
             } else {
-                None
-            }
+                ty
+            };
+            let Type::Path(path) = ty else {
+                return false;
+            };
+            POINTER_PATHS.iter().any(|(pointer, _, _)| {
+                type_utils::match_std_type_path(&path, pointer)
+                    .as_ref()
+                    .and_then(type_utils::last_type_arg)
+                    .is_some_and(|ty_inner| type_utils::is_shareable(&ty_inner, type_idents))
+            })
         })
-        .collect()
+        .count();
+    n_shareable_pointers >= 2
 }
 
 #[allow(clippy::type_complexity)]
 fn map_args<'a, I>(
     conversions: &mut Conversions,
+    dyn_impls: &mut DynImpls,
     candidates: &mut BTreeSet<OrdType>,
+    type_idents: Option<&[Ident]>,
     trait_path: Option<&Path>,
     self_ty: Option<&Type>,
     inputs: I,
@@ -856,7 +1971,14 @@ where
 {
     let (attrs, ident, ty, fmt, ser, de): (Vec<_>, Vec<_>, Vec<_>, Vec<_>, Vec<_>, Vec<_>) = inputs
         .into_iter()
-        .map(map_arg(conversions, candidates, trait_path, self_ty))
+        .map(map_arg(
+            conversions,
+            dyn_impls,
+            candidates,
+            type_idents,
+            trait_path,
+            self_ty,
+        ))
         .multiunzip();
 
     (attrs, ident, ty, fmt, ser, de)
@@ -864,7 +1986,9 @@ where
 
 fn map_arg<'a>(
     conversions: &'a mut Conversions,
+    dyn_impls: &'a mut DynImpls,
     candidates: &'a mut BTreeSet<OrdType>,
+    type_idents: Option<&'a [Ident]>,
     trait_path: Option<&'a Path>,
     self_ty: Option<&'a Type>,
 ) -> impl FnMut(&mut FnArg) -> (Attrs, Ident, Type, Stmt, FieldValue, Expr) + 'a {
@@ -912,10 +2036,19 @@ fn map_arg<'a>(
             }
         };
         let attrs = std::mem::take(fn_arg_attrs);
-        let (ty, ser, de) = if attrs.is_empty() {
-            map_typed_arg(conversions, candidates, &ident, &expr, &ty)
+        let (attrs, ty, ser, de) = if attrs.is_empty() {
+            map_typed_arg(
+                conversions,
+                dyn_impls,
+                candidates,
+                type_idents,
+                &ident,
+                &expr,
+                &ty,
+            )
         } else {
             (
+                attrs,
                 parse_quote! { #ty },
                 parse_quote! { #ident: <#ty as std::clone::Clone>::clone( & #expr ) },
                 parse_quote! { args.#ident },
@@ -927,24 +2060,64 @@ fn map_arg<'a>(
 
 fn map_typed_arg(
     conversions: &mut Conversions,
+    dyn_impls: &mut DynImpls,
     candidates: &mut BTreeSet<OrdType>,
+    type_idents: Option<&[Ident]>,
     ident: &Ident,
     expr: &Expr,
     ty: &Type,
-) -> (Type, FieldValue, Expr) {
+) -> (Attrs, Type, FieldValue, Expr) {
     candidates.insert(OrdType(ty.clone()));
     if let Some((arg_ty, used)) = conversions.get_mut(&OrdType(ty.clone())) {
         *used = true;
         return (
+            Attrs::new(),
             parse_quote! { #arg_ty },
             parse_quote! { #ident: <#arg_ty as test_fuzz::FromRef::<#ty>>::from_ref( & #expr ) },
             parse_quote! { <_ as test_fuzz::Into::<_>>::into(args.#ident) },
         );
     }
     match &ty {
-        Type::Path(path) => map_path_arg(conversions, candidates, ident, expr, path),
-        Type::Reference(ty) => map_ref_arg(conversions, candidates, ident, expr, ty),
+        Type::FnPtr(TypeFnPtr { inputs, output, .. }) => {
+            let underscores = inputs.iter().map(|_| quote! { _ });
+            let output_ty = match output {
+                ReturnType::Default => parse_quote! { () },
+                ReturnType::Type(_, ty) => ty.as_ref().clone(),
+            };
+            (
+                Attrs::new(),
+                parse_quote! { test_fuzz::runtime::FnName },
+                parse_quote! { #ident: test_fuzz::runtime::FnName::from_fn(&#expr) },
+                parse_quote! {
+                    args.#ident.to_fn::<#ty>().unwrap_or(|#(#underscores),*| {
+                        #[allow(unused_imports)]
+                        use test_fuzz::runtime::TryDefaultFallback;
+                        test_fuzz::runtime::TryDefault::<#output_ty>(std::marker::PhantomData)
+                            .default_or_panic()
+                    })
+                },
+            )
+        }
+        Type::Path(path) => map_path_arg(
+            conversions,
+            dyn_impls,
+            candidates,
+            type_idents,
+            ident,
+            expr,
+            path,
+        ),
+        Type::Reference(ty) => map_ref_arg(
+            conversions,
+            dyn_impls,
+            candidates,
+            type_idents,
+            ident,
+            expr,
+            ty,
+        ),
         _ => (
+            Attrs::new(),
             parse_quote! { #ty },
             parse_quote! { #ident: #expr.clone() },
             parse_quote! { args.#ident },
@@ -952,51 +2125,213 @@ fn map_typed_arg(
     }
 }
 
+// smoelius: Smart pointers, with the `serde_combinators` combinators that do and do not preserve
+// sharing. The latter are used when the pointed-to type cannot be shared (see
+// `type_utils::is_shareable`).
+const POINTER_PATHS: &[(&[&str], &str, &str)] = &[
+    (&["std", "rc", "Rc"], "SharedRcF", "RcF"),
+    (&["std", "sync", "Arc"], "SharedArcF", "ArcF"),
+];
+
+// smoelius: Cells that serde cannot handle on its own, with their `serde_combinators` combinators.
+const CELL_PATHS: &[(&[&str], &str)] = &[
+    (&["std", "cell", "RefCell"], "RefCellF"),
+    (&["std", "sync", "Mutex"], "MutexF"),
+    (&["std", "sync", "RwLock"], "RwLockF"),
+];
+
+const BOX_PATH: &[&str] = &["std", "boxed", "Box"];
+
+const COW_PATH: &[&str] = &["std", "borrow", "Cow"];
+
+/// Returns the paths of the standard library types that `map_path_arg` and `map_ref_arg` handle
+/// specially, i.e., the types that may be named by their last segments unless `no_std_conversions`
+/// is given.
+fn std_type_paths() -> Vec<&'static [&'static str]> {
+    [BOX_PATH, COW_PATH]
+        .into_iter()
+        .chain(OWNED_PATHS.iter().map(|&(path, _, _)| path))
+        .chain(POINTER_PATHS.iter().map(|&(path, _, _)| path))
+        .chain(CELL_PATHS.iter().map(|&(path, _)| path))
+        .collect()
+}
+
 fn map_path_arg(
     _conversions: &mut Conversions,
+    dyn_impls: &mut DynImpls,
     _candidates: &mut BTreeSet<OrdType>,
+    type_idents: Option<&[Ident]>,
     ident: &Ident,
     expr: &Expr,
     path: &TypePath,
-) -> (Type, FieldValue, Expr) {
+) -> (Attrs, Type, FieldValue, Expr) {
+    if let Some(args) = type_utils::match_std_type_path(path, BOX_PATH)
+        && let Some(ty) = type_utils::last_type_arg(&args)
+        && let Some((dyn_enum_path, used)) = dyn_impls.get_mut(&OrdType(ty))
+    {
+        *used = true;
+        return (
+            Attrs::new(),
+            parse_quote! { #dyn_enum_path },
+            parse_quote! { #ident: #dyn_enum_path::from_dyn(&*#expr) },
+            parse_quote! { args.#ident.into_box() },
+        );
+    }
+    if let Some(args) = type_utils::match_std_type_path(path, COW_PATH)
+        && let Some(ty) = type_utils::last_type_arg(&args)
+    {
+        return (
+            Attrs::new(),
+            parse_quote! { <#ty as std::borrow::ToOwned>::Owned },
+            parse_quote! { #ident: #expr.clone().into_owned() },
+            parse_quote! { std::borrow::Cow::Owned(args.#ident) },
+        );
+    }
+    for (pointer, shared_combinator, combinator) in POINTER_PATHS {
+        let Some(ty_inner) = type_utils::match_std_type_path(path, pointer)
+            .as_ref()
+            .and_then(type_utils::last_type_arg)
+        else {
+            continue;
+        };
+        let shareable =
+            type_idents.is_some_and(|type_idents| type_utils::is_shareable(&ty_inner, type_idents));
+        let cell_combinator = if let Type::Path(path_inner) = &ty_inner {
+            CELL_PATHS.iter().find_map(|(cell, cell_combinator)| {
+                type_utils::match_std_type_path(path_inner, cell).map(|_| *cell_combinator)
+            })
+        } else {
+            None
+        };
+        if !shareable && cell_combinator.is_none() {
+            break;
+        }
+        let pointer_combinator = if shareable {
+            shared_combinator
+        } else {
+            combinator
+        };
+        let combinators = std::iter::once(*pointer_combinator)
+            .chain(cell_combinator)
+            .collect::<Vec<_>>();
+        return (
+            vec![serde_with_attr(&combinators)],
+            parse_quote! { #path },
+            parse_quote! { #ident: #expr.clone() },
+            parse_quote! { args.#ident },
+        );
+    }
     (
+        Attrs::new(),
         parse_quote! { #path },
         parse_quote! { #ident: #expr.clone() },
         parse_quote! { args.#ident },
     )
 }
 
+/// Returns a serde attribute that serializes and deserializes using `combinators`, outermost first,
+/// applied to `serde_combinators::Type`.
+fn serde_with_attr(combinators: &[&str]) -> Attribute {
+    let with = combinators.iter().rev().fold(
+        String::from("test_fuzz::serde_combinators::Type<_>"),
+        |with, combinator| format!("test_fuzz::serde_combinators::{combinator}<{with}>"),
+    );
+    let serialize_with =
+        format!("<{with} as test_fuzz::serde_combinators::SerializeWith>::serialize");
+    let deserialize_with =
+        format!("<{with} as test_fuzz::serde_combinators::DeserializeWith>::deserialize");
+    parse_quote! {
+        #[serde(serialize_with = #serialize_with, deserialize_with = #deserialize_with)]
+    }
+}
+
+// smoelius: Unsized types other than `str` and slices that have an owned counterpart. Each entry
+// is the unsized type's path, the owned type, and the suffix of the owned type's `as_...` method.
+const OWNED_PATHS: &[(&[&str], &str, &str)] = &[
+    (&["std", "ffi", "CStr"], "std::ffi::CString", "c_str"),
+    (&["std", "ffi", "OsStr"], "std::ffi::OsString", "os_str"),
+    (&["std", "path", "Path"], "std::path::PathBuf", "path"),
+];
+
 fn map_ref_arg(
     conversions: &mut Conversions,
+    dyn_impls: &mut DynImpls,
     candidates: &mut BTreeSet<OrdType>,
+    type_idents: Option<&[Ident]>,
     ident: &Ident,
     expr: &Expr,
     ty: &TypeReference,
-) -> (Type, FieldValue, Expr) {
+) -> (Attrs, Type, FieldValue, Expr) {
     let (maybe_mut, mutability) = if ty.mutability.is_some() {
         ("mut_", quote! { mut })
     } else {
         ("", quote! {})
     };
     let ty = &*ty.elem;
+    if let Some((dyn_enum_path, used)) = dyn_impls.get_mut(&OrdType(ty.clone())) {
+        *used = true;
+        let as_maybe_mut_dyn = Ident::new(&format!("as_{maybe_mut}dyn"), Span::call_site());
+        return (
+            Attrs::new(),
+            parse_quote! { #dyn_enum_path },
+            parse_quote! { #ident: #dyn_enum_path::from_dyn(#expr) },
+            parse_quote! { args.#ident.#as_maybe_mut_dyn() },
+        );
+    }
     match ty {
         Type::Path(path) => {
             if type_utils::match_type_path(path, &["str"]) == Some(PathArguments::None) {
                 let as_maybe_mut_str = Ident::new(&format!("as_{maybe_mut}str"), Span::call_site());
-                (
+                return (
+                    Attrs::new(),
                     parse_quote! { String },
                     parse_quote! { #ident: #expr.to_owned() },
                     parse_quote! { args.#ident.#as_maybe_mut_str() },
-                )
-            } else {
-                let expr = parse_quote! { (*#expr) };
-                let (ty, ser, de) = map_path_arg(conversions, candidates, ident, &expr, path);
-                (ty, ser, parse_quote! { & #mutability #de })
+                );
+            }
+            for (unsized_path, owned, suffix) in OWNED_PATHS {
+                if type_utils::match_std_type_path(path, unsized_path) == Some(PathArguments::None)
+                {
+                    let owned = parse_str::<Type>(owned).expect("Could not parse owned type");
+                    // smoelius: `PathBuf`, `OsString`, and `CString` have no `as_mut_...` methods.
+                    let de = if maybe_mut.is_empty() {
+                        let as_suffix = Ident::new(&format!("as_{suffix}"), Span::call_site());
+                        parse_quote! { args.#ident.#as_suffix() }
+                    } else {
+                        let error = syn::Error::new_spanned(
+                            path,
+                            format!(
+                                "`&mut {}` arguments are not supported",
+                                path.to_token_stream().to_string().replace(' ', "")
+                            ),
+                        )
+                        .into_compile_error();
+                        parse_quote! { #error }
+                    };
+                    return (
+                        Attrs::new(),
+                        owned,
+                        parse_quote! { #ident: #expr.to_owned() },
+                        de,
+                    );
+                }
             }
+            let expr = parse_quote! { (*#expr) };
+            let (attrs, ty, ser, de) = map_path_arg(
+                conversions,
+                dyn_impls,
+                candidates,
+                type_idents,
+                ident,
+                &expr,
+                path,
+            );
+            (attrs, ty, ser, parse_quote! { & #mutability #de })
         }
         Type::Slice(TypeSlice { elem, .. }) => {
             let as_maybe_mut_slice = Ident::new(&format!("as_{maybe_mut}slice"), Span::call_site());
             (
+                Attrs::new(),
                 parse_quote! { Vec<#elem> },
                 parse_quote! { #ident: #expr.to_vec() },
                 parse_quote! { args.#ident.#as_maybe_mut_slice() },
@@ -1004,19 +2339,26 @@ fn map_ref_arg(
         }
         _ => {
             let expr = parse_quote! { (*#expr) };
-            let (ty, ser, de) = map_typed_arg(conversions, candidates, ident, &expr, ty);
-            (ty, ser, parse_quote! { & #mutability #de })
+            let (attrs, ty, ser, de) = map_typed_arg(
+                conversions,
+                dyn_impls,
+                candidates,
+                type_idents,
+                ident,
+                &expr,
+                ty,
+            );
+            (attrs, ty, ser, parse_quote! { & #mutability #de })
         }
     }
 }
 
-fn opts_from_attr(attr: &Attribute) -> TestFuzzOpts {
+fn opts_from_attr(attr: &Attribute) -> syn::Result<TestFuzzOpts> {
     attr.parse_args::<TokenStream2>().map_or_else(
-        |_| TestFuzzOpts::default(),
+        |_| Ok(TestFuzzOpts::default()),
         |tokens| {
-            let attr_args =
-                NestedMeta::parse_meta_list(tokens).expect("Could not parse attribute args");
-            TestFuzzOpts::from_list(&attr_args).expect("Could not parse `test_fuzz` options")
+            let attr_args = NestedMeta::parse_meta_list(tokens)?;
+            Ok(TestFuzzOpts::from_list(&attr_args)?)
         },
     )
 }
@@ -1028,13 +2370,63 @@ fn is_test_fuzz(attr: &Attribute) -> bool {
         .all(|PathSegment { ident, .. }| ident == "test_fuzz")
 }
 
-fn parse_generic_arguments(s: &str) -> Punctuated<GenericArgument, token::Comma> {
-    let tokens = TokenStream::from_str(s).expect("Could not tokenize string");
-    Parser::parse(
+fn parse_dyn_impls(lit: &LitStr) -> syn::Result<(Type, Vec<Type>)> {
+    let (ty, impls) = parse_lit_str(
+        lit,
+        |input: ParseStream| {
+            let ty = input.parse::<Type>()?;
+            input.parse::<token::Colon>()?;
+            let impls = Punctuated::<Type, token::Comma>::parse_terminated(input)?;
+            Ok((ty, impls.into_iter().collect::<Vec<_>>()))
+        },
+        "dyn_impls",
+    )?;
+    if !matches!(ty, Type::TraitObject(_)) || type_utils::type_base(&ty).is_none() {
+        return Err(syn::Error::new(
+            lit.span(),
+            "`dyn_impls` argument does not begin with a trait object type; it should have the \
+             form \"dyn Trait: X, Y, ...\"",
+        ));
+    }
+    if impls.is_empty() {
+        return Err(syn::Error::new(
+            lit.span(),
+            "`dyn_impls` argument does not list any implementors; it should have the form \"dyn \
+             Trait: X, Y, ...\"",
+        ));
+    }
+    if let Some(ty) = impls.iter().find(|ty| type_utils::type_base(ty).is_none()) {
+        return Err(syn::Error::new(
+            lit.span(),
+            format!(
+                "`dyn_impls` implementor `{}` is not a named type",
+                ty.to_token_stream()
+            ),
+        ));
+    }
+    Ok((ty, impls))
+}
+
+fn parse_generic_arguments(
+    lit: &LitStr,
+    option: &str,
+) -> syn::Result<Punctuated<GenericArgument, token::Comma>> {
+    parse_lit_str(
+        lit,
         Punctuated::<GenericArgument, token::Comma>::parse_terminated,
-        tokens,
+        option,
     )
-    .expect("Could not parse generic arguments")
+}
+
+/// Parses the contents of `lit`, an argument of the `test_fuzz` option named `option`. Errors are
+/// reported at `lit`.
+fn parse_lit_str<F: Parser>(lit: &LitStr, parser: F, option: &str) -> syn::Result<F::Output> {
+    lit.parse_with(parser).map_err(|error| {
+        syn::Error::new(
+            lit.span(),
+            format!("Could not parse `{option}` argument: {error}"),
+        )
+    })
 }
 
 #[cfg(fuzzing)]
@@ -1177,12 +2569,27 @@ fn mod_ident(opts: &TestFuzzOpts, self_ty_base: Option<&Ident>, target_ident: &I
         }
         s.push_str(&target_ident.to_string());
     }
+    if let Some(suffix) = &opts.instantiation {
+        s.push('_');
+        s.push_str(suffix);
+    }
     s.push_str("_fuzz__");
     Ident::new(&s, Span::call_site())
 }
 
 static INDEX: AtomicU32 = AtomicU32::new(0);
 
+/// Generates an expression that describes the outcome stored in `ret`, a
+/// [`std::thread::Result`].
+fn outcome(ret: &Ident) -> TokenStream2 {
+    quote! {
+        test_fuzz::runtime::outcome(&#ret, |ret| {
+            use test_fuzz::runtime::TryDebugFallback;
+            test_fuzz::runtime::TryDebug(ret).apply(&mut |value| format!("{value:?}"))
+        })
+    }
+}
+
 fn anonymous_ident() -> Ident {
     let index = INDEX.fetch_add(1, Ordering::SeqCst);
     Ident::new(&format!("_{index}"), Span::call_site())
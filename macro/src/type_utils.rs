@@ -2,9 +2,12 @@ use proc_macro2::{Punct, Spacing, Span, TokenStream, TokenTree};
 use quote::ToTokens;
 use std::collections::BTreeMap;
 use syn::{
-    GenericArgument, Ident, Path, PathArguments, PathSegment, Type, TypePath, parse_quote,
-    visit::{Visit, visit_path_arguments},
-    visit_mut::{VisitMut, visit_type_mut},
+    GenericArgument, Ident, Lifetime, Path, PathArguments, PathSegment, Type, TypeImplTrait,
+    TypeParamBound, TypePath,
+    ext::IdentExt,
+    parse_quote,
+    visit::{Visit, visit_path, visit_path_arguments, visit_type},
+    visit_mut::{VisitMut, visit_type_mut, visit_type_path_mut},
 };
 
 pub fn map_path_generic_params(map: &BTreeMap<&Ident, &GenericArgument>, path: &Path) -> Path {
@@ -171,11 +174,190 @@ pub fn match_type_path(path: &TypePath, other: &[&str]) -> Option<PathArguments>
     }
 }
 
+// smoelius: Standard library types that can be named by their last segments alone without a
+// `use` declaration.
+const PRELUDE_PATHS: &[&[&str]] = &[&["std", "boxed", "Box"]];
+
+/// Like [`match_type_path`], but `other` is the full path of a standard library type. If that type
+/// is in the prelude (e.g., `Box`), `path` may also be just its last segment.
+///
+/// Other types must be named by their full paths, because a macro cannot tell whether, e.g.,
+/// `Path` refers to `std::path::Path` or to some other type. See also [`expand_std_type_paths`].
+pub fn match_std_type_path(path: &TypePath, other: &[&str]) -> Option<PathArguments> {
+    match_type_path(path, other).or_else(|| {
+        if PRELUDE_PATHS.contains(&other) {
+            other.last().and_then(|last| match_type_path(path, &[last]))
+        } else {
+            None
+        }
+    })
+}
+
+/// Replaces each type path in `ty` that consists of just the last segment of one of `std_paths`
+/// with that full path, e.g., `Path` with `std::path::Path`.
+pub fn expand_std_type_paths(std_paths: &[&[&str]], ty: &mut Type) {
+    let mut visitor = StdTypePathVisitor { std_paths };
+    visitor.visit_type_mut(ty);
+}
+
+struct StdTypePathVisitor<'a> {
+    std_paths: &'a [&'a [&'a str]],
+}
+
+impl VisitMut for StdTypePathVisitor<'_> {
+    fn visit_type_path_mut(&mut self, ty: &mut TypePath) {
+        if ty.qself.is_none()
+            && ty.path.leading_colon.is_none()
+            && let [segment] = ty.path.segments.iter().collect::<Vec<_>>().as_slice()
+            && let Some(std_path) = self
+                .std_paths
+                .iter()
+                .find(|std_path| std_path.last().is_some_and(|last| segment.ident == last))
+        {
+            let arguments = segment.arguments.clone();
+            ty.path.segments = std_path
+                .iter()
+                .map(|s| PathSegment::from(Ident::new(s, segment.ident.span())))
+                .collect();
+            if let Some(last) = ty.path.segments.last_mut() {
+                last.arguments = arguments;
+            }
+        }
+        visit_type_path_mut(self, ty);
+    }
+}
+
+pub fn last_type_arg(args: &PathArguments) -> Option<Type> {
+    if let PathArguments::AngleBracketed(args) = args {
+        args.args.iter().rev().find_map(|arg| {
+            if let GenericArgument::Type(ty) = arg {
+                Some(ty.clone())
+            } else {
+                None
+            }
+        })
+    } else {
+        None
+    }
+}
+
+/// Determines whether `ty` can be pointed to by a sharing-preserving `serde_combinators`
+/// combinator. Such combinators require the pointed-to type to be sized and `'static`. Since
+/// lifetimes and type parameters cannot be checked for `'static`, their presence makes `ty`
+/// unshareable.
+pub fn is_shareable(ty: &Type, type_idents: &[Ident]) -> bool {
+    let mut visitor = ShareableVisitor {
+        type_idents,
+        shareable: !is_unsized(ty),
+    };
+    visitor.visit_type(ty);
+    visitor.shareable
+}
+
+const UNSIZED_PATHS: &[&[&str]] = &[
+    &["std", "ffi", "CStr"],
+    &["std", "ffi", "OsStr"],
+    &["std", "path", "Path"],
+];
+
+fn is_unsized(ty: &Type) -> bool {
+    match ty {
+        Type::Slice(_) | Type::TraitObject(_) => true,
+        // smoelius: A bare `CStr`, `OsStr`, or `Path` might not refer to the standard library type.
+        // But assume that it does, since treating a type as unsized only makes it unshareable.
+        Type::Path(path) => {
+            match_type_path(path, &["str"]).is_some()
+                || UNSIZED_PATHS.iter().any(|unsized_path| {
+                    match_type_path(path, unsized_path).is_some()
+                        || unsized_path
+                            .last()
+                            .is_some_and(|last| match_type_path(path, &[last]).is_some())
+                })
+        }
+        _ => false,
+    }
+}
+
+struct ShareableVisitor<'a> {
+    type_idents: &'a [Ident],
+    shareable: bool,
+}
+
+impl<'ast> Visit<'ast> for ShareableVisitor<'_> {
+    fn visit_lifetime(&mut self, lifetime: &'ast Lifetime) {
+        if lifetime.ident != "static" {
+            self.shareable = false;
+        }
+    }
+
+    fn visit_type(&mut self, ty: &'ast Type) {
+        if let Type::ImplTrait(_) | Type::Infer(_) = ty {
+            self.shareable = false;
+        }
+        visit_type(self, ty);
+    }
+
+    // smoelius: Check the first segment rather than requiring a lone identifier, so that paths like
+    // `T::Output` are caught. An unexpanded `Self` may stand for a type involving the impl's type
+    // parameters, so it is treated like a type parameter.
+    fn visit_path(&mut self, path: &'ast Path) {
+        if path.leading_colon.is_none()
+            && let Some(segment) = path.segments.first()
+            && (segment.ident == "Self" || self.type_idents.contains(&segment.ident))
+        {
+            self.shareable = false;
+        }
+        visit_path(self, path);
+    }
+}
+
+/// Returns the first `impl Trait` type within `ty`, if any.
+pub fn find_impl_trait(ty: &Type) -> Option<&TypeImplTrait> {
+    let mut visitor = ImplTraitVisitor { impl_trait: None };
+    visitor.visit_type(ty);
+    visitor.impl_trait
+}
+
+struct ImplTraitVisitor<'ast> {
+    impl_trait: Option<&'ast TypeImplTrait>,
+}
+
+impl<'ast> Visit<'ast> for ImplTraitVisitor<'ast> {
+    fn visit_type_impl_trait(&mut self, impl_trait: &'ast TypeImplTrait) {
+        self.impl_trait.get_or_insert(impl_trait);
+    }
+}
+
 pub fn type_base(ty: &Type) -> Option<&Ident> {
-    if let Type::Path(path) = ty
-        && let Some(segment) = path.path.segments.last()
+    type_path(ty).and_then(|path| path.segments.last().map(|segment| &segment.ident))
+}
+
+/// Returns an identifier formed from `prefix` and each segment of `ty`'s path, with each segment
+/// preceded by its length, e.g., `Dyn` and `dyn a::Handler` yield `Dyn_1a_7Handler`. The lengths
+/// make the result unique, e.g., `a_b::Echo` and `a::b_Echo` yield `Dyn_3a_b_4Echo` and
+/// `Dyn_1a_6b_Echo`. Unlike [`type_base`], the result distinguishes types with the same name in
+/// different modules.
+pub fn type_path_ident(prefix: &str, ty: &Type) -> Option<Ident> {
+    let path = type_path(ty)?;
+    let name = path
+        .segments
+        .iter()
+        .fold(prefix.to_owned(), |name, segment| {
+            let ident = segment.ident.unraw().to_string();
+            format!("{name}_{}{ident}", ident.len())
+        });
+    Some(Ident::new(&name, Span::call_site()))
+}
+
+fn type_path(ty: &Type) -> Option<&Path> {
+    if let Type::Path(path) = ty {
+        return Some(&path.path);
+    }
+
+    if let Type::TraitObject(trait_object) = ty
+        && let Some(TypeParamBound::Trait(bound)) = trait_object.bounds.first()
     {
-        return Some(&segment.ident);
+        return Some(&bound.path);
     }
 
     None
@@ -0,0 +1,17 @@
+use std::sync::LazyLock;
+
+/// A marker whose presence in the running binary enables capturing. Registered by the
+/// `capture_hook!` macro, and by the `test_fuzz` macro in a package's own tests.
+pub struct CaptureHook;
+
+inventory::collect!(CaptureHook);
+
+static HOOKED: LazyLock<bool> =
+    LazyLock::new(|| inventory::iter::<CaptureHook>.into_iter().next().is_some());
+
+/// Returns true if calls to fuzz targets should be written to corpus files, i.e., if the running
+/// binary contains a [`CaptureHook`] and is not being fuzzed.
+#[must_use]
+pub fn capturing() -> bool {
+    *HOOKED && !crate::test_fuzz_enabled()
+}
@@ -1,25 +1,39 @@
 use internal::{
     dirs::{
         corpus_directory_from_args_type, generic_args_directory_from_args_type,
-        impl_generic_args_directory_from_args_type,
+        impl_generic_args_directory_from_args_type, ret_directory_from_args_type,
     },
     serde_format,
 };
 use serde::{Serialize, de::DeserializeOwned};
+use serde_combinators::shared_scope;
 use sha1::{Digest, Sha1};
 use std::{
-    any::type_name,
+    any::{Any, type_name},
     env,
     fmt::{self, Debug, Formatter},
-    fs::{create_dir_all, write},
+    fs::{File, create_dir_all, read_dir, read_to_string, write},
     io::{self, Read, Write},
     marker::PhantomData,
-    path::Path,
+    panic::{AssertUnwindSafe, catch_unwind},
+    path::{Path, PathBuf},
     sync::Once,
+    thread,
+    time::Instant,
 };
 
+pub use inventory;
 pub use num_traits;
 
+mod capture;
+pub use capture::{CaptureHook, capturing};
+
+mod registry;
+pub use registry::{FnName, RegisteredFn, TryDefault, TryDefaultFallback};
+
+mod sequence;
+pub use sequence::{SequenceHooks, SequenceId, SequenceLog};
+
 pub mod traits;
 
 // smoelius: TryDebug, etc. use Nikolai Vazquez's trick from `impls`.
@@ -147,6 +161,11 @@ pub fn display_enabled() -> bool {
     enabled("DISPLAY")
 }
 
+#[must_use]
+pub fn location_enabled() -> bool {
+    enabled("LOCATION")
+}
+
 #[must_use]
 pub fn pretty_print_enabled() -> bool {
     enabled("PRETTY_PRINT")
@@ -162,6 +181,68 @@ pub fn write_enabled() -> bool {
     enabled("WRITE")
 }
 
+/// Prefix of the line that [`print_location`] writes to standard output
+pub const LOCATION_PREFIX: &str = "test-fuzz location: ";
+
+/// Prints the location of a fuzz target's `test_fuzz` attribute, as given by `file!()` and
+/// `line!()`. `cargo test-fuzz --changed-since` uses the location to map the target to its source.
+pub fn print_location(file: &str, line: u32) {
+    #[allow(clippy::explicit_write)]
+    writeln!(io::stdout(), "{LOCATION_PREFIX}{file}:{line}").unwrap();
+}
+
+/// Prefix of the line that [`replay`] writes to standard error before replaying each file in a
+/// `TEST_FUZZ_REPLAY_DIR`.
+///
+/// The prefix is followed by the file's name.
+pub const REPLAY_FILE_PREFIX: &str = "test-fuzz replay file: ";
+
+/// Prefix of the line that [`replay`] writes to standard error after replaying each file in a
+/// `TEST_FUZZ_REPLAY_DIR`.
+///
+/// The prefix is followed by `ok` or `panicked`, a space, and the number of microseconds the replay
+/// took.
+pub const REPLAY_RESULT_PREFIX: &str = "test-fuzz replay result: ";
+
+#[must_use]
+pub fn replay_dir() -> Option<PathBuf> {
+    env::var_os("TEST_FUZZ_REPLAY_DIR").map(PathBuf::from)
+}
+
+/// Calls `f` with standard input or, if `TEST_FUZZ_REPLAY_DIR` is set, with each file in that
+/// directory in order of name.
+///
+/// In the latter case, panics are caught, and each file's output on standard error is delimited by
+/// lines beginning with [`REPLAY_FILE_PREFIX`] and [`REPLAY_RESULT_PREFIX`]. This allows
+/// `cargo test-fuzz` to replay many files in one process and still attribute output and panics to
+/// individual files.
+pub fn replay(mut f: impl FnMut(&mut dyn Read)) {
+    let Some(dir) = replay_dir() else {
+        f(&mut io::stdin());
+        return;
+    };
+    let mut paths = read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.is_file() && path.file_name() != Some("README.txt".as_ref()))
+        .collect::<Vec<_>>();
+    paths.sort();
+    for path in paths {
+        let mut file = File::open(&path).unwrap();
+        eprintln!(
+            "{REPLAY_FILE_PREFIX}{}",
+            path.file_name().unwrap_or_default().to_string_lossy()
+        );
+        let start = Instant::now();
+        let result = catch_unwind(AssertUnwindSafe(|| f(&mut file)));
+        eprintln!(
+            "{REPLAY_RESULT_PREFIX}{} {}",
+            if result.is_ok() { "ok" } else { "panicked" },
+            start.elapsed().as_micros()
+        );
+    }
+}
+
 #[must_use]
 fn enabled(opt: &str) -> bool {
     let key = "TEST_FUZZ".to_owned() + if opt.is_empty() { "" } else { "_" } + opt;
@@ -180,22 +261,75 @@ pub fn write_generic_args<T>(args: &[&str]) {
     write_data(&generic_args, data.as_bytes()).unwrap();
 }
 
-pub fn write_args<T: Serialize>(args: &T) {
+/// Returns the path of the corpus file written.
+pub fn write_args<T: Serialize>(args: &T) -> PathBuf {
     let corpus = corpus_directory_from_args_type::<T>();
-    let data = serde_format::serialize(args);
-    write_data(&corpus, &data).unwrap();
+    let data = shared_scope(|| serde_format::serialize(args));
+    write_data(&corpus, &data).unwrap()
+}
+
+/// Like [`write_args`], but also writes `globals`, which are captured global variables. The
+/// corpus directory is determined by `T` alone.
+pub fn write_args_and_globals<T: Serialize, G: Serialize>(args: &T, globals: &G) -> PathBuf {
+    let corpus = corpus_directory_from_args_type::<T>();
+    let data = shared_scope(|| serde_format::serialize(&(args, globals)));
+    write_data(&corpus, &data).unwrap()
+}
+
+/// Writes `outcome` to a file whose name is the same as `corpus_file`'s, but in the target's
+/// return value directory.
+pub fn write_ret<T>(corpus_file: &Path, outcome: &str) {
+    let ret = ret_directory_from_args_type::<T>();
+    create_dir_all(&ret).unwrap_or_default();
+    let file_name = corpus_file.file_name().unwrap();
+    write(ret.join(file_name), outcome).unwrap();
+}
+
+/// Returns the recorded outcome that the current call's outcome should match, if any.
+#[must_use]
+pub fn expected_ret() -> Option<String> {
+    let path = env::var_os("TEST_FUZZ_EXPECTED_RET")?;
+    Some(read_to_string(path).unwrap())
+}
+
+/// Describes the outcome of a call: `Ret(..)` if the call returned, or `Panic(..)` if it
+/// panicked. `debug` should format a returned value.
+pub fn outcome<T>(result: &thread::Result<T>, debug: impl FnOnce(&T) -> String) -> String {
+    match result {
+        Ok(ret) => format!("Ret({})", debug(ret)),
+        Err(payload) => format!("Panic({:?})", panic_message(payload.as_ref())),
+    }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("<non-string payload>")
+}
+
+/// The outcome that `check_ret` is given when a corpus file's arguments cannot be deserialized
+pub const UNDESERIALIZABLE_ARGS: &str = "<arguments could not be deserialized>";
+
+pub fn check_ret(expected: &str, actual: &str) {
+    assert!(
+        expected == actual,
+        "Outcome differs from recorded outcome\n  expected: {expected}\n    actual: {actual}"
+    );
 }
 
-pub fn write_data(dir: &Path, data: &[u8]) -> io::Result<()> {
+pub fn write_data(dir: &Path, data: &[u8]) -> io::Result<PathBuf> {
     create_dir_all(dir).unwrap_or_default();
     let hex = {
         let digest = Sha1::digest(data);
         hex::encode(digest)
     };
     let path_buf = dir.join(hex);
-    write(path_buf, data)
+    write(&path_buf, data)?;
+    Ok(path_buf)
 }
 
 pub fn read_args<T: DeserializeOwned, R: Read>(reader: R) -> Option<T> {
-    serde_format::deserialize(reader)
+    shared_scope(|| serde_format::deserialize(reader))
 }
@@ -0,0 +1,99 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    any::{Any, type_name},
+    fmt::{self, Debug, Formatter},
+    marker::PhantomData,
+};
+
+/// A function registered with the `register_fn` macro.
+pub struct RegisteredFn {
+    pub name: &'static str,
+    /// Returns the function as a function pointer, boxed so that its type can be checked.
+    pub get: fn() -> Box<dyn Any>,
+}
+
+inventory::collect!(RegisteredFn);
+
+/// A function pointer, serialized as the name under which it was registered. A function pointer
+/// that was not registered (e.g., one coerced from a closure) is serialized without a name.
+///
+/// A function pointer is matched to a registered function by comparing addresses. Rust does not
+/// guarantee that a function has a unique address: its address can differ between codegen units,
+/// and distinct functions with identical bodies can be merged. So, a registered function may fail
+/// to match, or may match under another function's name.
+#[derive(Clone, Deserialize, Serialize)]
+pub struct FnName(Option<String>);
+
+impl FnName {
+    /// Returns the name of the registered function equal to `f`. If there is no such function, a
+    /// warning is written to standard error.
+    pub fn from_fn<F: Any + PartialEq>(f: &F) -> Self {
+        let name = inventory::iter::<RegisteredFn>
+            .into_iter()
+            .find(|registered| (registered.get)().downcast_ref::<F>() == Some(f))
+            .map(|registered| registered.name.to_owned());
+        if name.is_none() {
+            eprintln!(
+                "Warning: Function pointer of type `{}` does not match a registered function; it \
+                 will be replaced with a function that returns `Default::default()` when replayed",
+                type_name::<F>()
+            );
+        }
+        Self(name)
+    }
+
+    /// Returns the registered function with this name, or `None` if the function pointer was not
+    /// registered when it was recorded.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no registered function of type `F` has this name, e.g., because the function was
+    /// renamed after the corpus file was recorded.
+    #[must_use]
+    pub fn to_fn<F: Any + Copy>(&self) -> Option<F> {
+        let name = self.0.as_deref()?;
+        let f = inventory::iter::<RegisteredFn>
+            .into_iter()
+            .filter(|registered| registered.name == name)
+            .find_map(|registered| (registered.get)().downcast_ref::<F>().copied());
+        assert!(
+            f.is_some(),
+            "No function of type `{}` is registered as `{name}`",
+            type_name::<F>()
+        );
+        f
+    }
+}
+
+impl Debug for FnName {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0.as_deref().unwrap_or("<unregistered>"))
+    }
+}
+
+// smoelius: `TryDefault` uses the same trick as `TryDebug`. An unregistered function pointer is
+// replaced with a function that returns `Default::default()` when the return type implements
+// `Default`, and that panics otherwise. Thus, only function pointers that actually take the
+// fallback path require `Default`.
+
+pub struct TryDefault<T>(pub PhantomData<T>);
+
+impl<T: Default> TryDefault<T> {
+    #[must_use]
+    pub fn default_or_panic(&self) -> T {
+        T::default()
+    }
+}
+
+pub trait TryDefaultFallback<T> {
+    fn default_or_panic(&self) -> T;
+}
+
+impl<T> TryDefaultFallback<T> for TryDefault<T> {
+    fn default_or_panic(&self) -> T {
+        panic!(
+            "Cannot call unregistered function pointer: `{}` does not implement `Default`",
+            type_name::<T>()
+        )
+    }
+}
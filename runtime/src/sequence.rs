@@ -0,0 +1,190 @@
+use crate::write_data;
+use internal::{dirs::corpus_directory_from_args_type, serde_format};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_combinators::shared_scope;
+use std::{
+    collections::HashMap,
+    fmt::{self, Debug, Formatter},
+    hash::{Hash, Hasher},
+    rc::Rc,
+    sync::{
+        OnceLock,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Identifies a receiver whose calls are recorded as a sequence. A receiver's type should hold a
+/// `SequenceId` in the field named by `test_fuzz_impl`'s `sequence_id` option.
+///
+/// A sequence starts whenever a `SequenceId` is created, e.g., by `Default::default` in the
+/// receiver's constructor, or by deserialization. Cloning a `SequenceId` forks its sequence, i.e.,
+/// starts a sequence that begins with the calls recorded so far. Dropping a `SequenceId` ends its
+/// sequence, which is then written to a corpus file.
+///
+/// `SequenceId`s are ignored when comparing, hashing, and serializing receivers.
+pub struct SequenceId {
+    id: u64,
+    hooks: OnceLock<&'static SequenceHooks>,
+}
+
+/// Functions that act on the recorded sequences of one receiver type. The `test_fuzz_impl` macro
+/// generates one `SequenceHooks` for each type that uses `sequence`.
+pub struct SequenceHooks {
+    /// Starts the sequence of the second id with the calls of the first
+    pub fork: fn(u64, u64),
+    /// Ends the sequence of the id
+    pub end: fn(u64),
+}
+
+impl SequenceId {
+    #[must_use]
+    pub const fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Sets the functions to call when this `SequenceId` is cloned or dropped. Called when the
+    /// first call on the receiver is recorded.
+    pub fn set_hooks(&self, hooks: &'static SequenceHooks) {
+        let _ = self.hooks.set(hooks);
+    }
+}
+
+impl Default for SequenceId {
+    fn default() -> Self {
+        Self {
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            hooks: OnceLock::new(),
+        }
+    }
+}
+
+impl Clone for SequenceId {
+    fn clone(&self) -> Self {
+        let clone = Self::default();
+        if let Some(hooks) = self.hooks.get() {
+            (hooks.fork)(self.id, clone.id);
+            clone.set_hooks(hooks);
+        }
+        clone
+    }
+}
+
+impl Drop for SequenceId {
+    fn drop(&mut self) {
+        if let Some(hooks) = self.hooks.get() {
+            (hooks.end)(self.id);
+        }
+    }
+}
+
+impl Debug for SequenceId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("SequenceId")
+    }
+}
+
+impl PartialEq for SequenceId {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for SequenceId {}
+
+impl Hash for SequenceId {
+    fn hash<H: Hasher>(&self, _: &mut H) {}
+}
+
+impl Serialize for SequenceId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_unit()
+    }
+}
+
+impl<'de> Deserialize<'de> for SequenceId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        <()>::deserialize(deserializer).map(|()| Self::default())
+    }
+}
+
+/// The sequences of calls recorded on one thread for one receiver type, keyed by `SequenceId`.
+///
+/// A sequence is written to a corpus file once, when it ends. Sequences that have not ended when
+/// the thread exits (e.g., because their receivers were leaked or moved to other threads) are
+/// written then. A forked sequence is written only if calls were recorded on it after the fork.
+/// Otherwise, it would duplicate a prefix of its parent, e.g., when a receiver is cloned only to
+/// be inspected.
+pub struct SequenceLog<C: Serialize> {
+    sequences: HashMap<u64, Sequence<C>>,
+}
+
+struct Sequence<C> {
+    calls: Vec<Rc<C>>,
+    extended: bool,
+}
+
+impl<C: Serialize> Default for SequenceLog<C> {
+    fn default() -> Self {
+        Self {
+            sequences: HashMap::new(),
+        }
+    }
+}
+
+impl<C: Serialize> SequenceLog<C> {
+    pub fn record(&mut self, id: u64, call: C) {
+        let sequence = self.sequences.entry(id).or_insert_with(|| Sequence {
+            calls: Vec::new(),
+            extended: false,
+        });
+        sequence.calls.push(Rc::new(call));
+        sequence.extended = true;
+    }
+
+    pub fn fork(&mut self, from: u64, to: u64) {
+        if let Some(sequence) = self.sequences.get(&from) {
+            let calls = sequence.calls.clone();
+            self.sequences.insert(
+                to,
+                Sequence {
+                    calls,
+                    extended: false,
+                },
+            );
+        }
+    }
+
+    pub fn end(&mut self, id: u64) {
+        if let Some(sequence) = self.sequences.remove(&id) {
+            sequence.write();
+        }
+    }
+}
+
+impl<C: Serialize> Drop for SequenceLog<C> {
+    fn drop(&mut self) {
+        for sequence in self.sequences.values() {
+            sequence.write();
+        }
+    }
+}
+
+impl<C: Serialize> Sequence<C> {
+    fn write(&self) {
+        if self.extended {
+            write_sequence(&self.calls);
+        }
+    }
+}
+
+/// Writes `calls` to a corpus file in the directory determined by `C`.
+fn write_sequence<C: Serialize>(calls: &[Rc<C>]) {
+    // smoelius: The calls are serialized through plain references so that the file deserializes
+    // as a `Vec<C>`. The `Rc`s only let forked sequences share calls in memory.
+    let calls = calls.iter().map(Rc::as_ref).collect::<Vec<_>>();
+    let corpus = corpus_directory_from_args_type::<C>();
+    let data = shared_scope(|| serde_format::serialize(&calls));
+    write_data(&corpus, &data).unwrap();
+}
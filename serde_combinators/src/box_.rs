@@ -0,0 +1,36 @@
+use super::{DeserializeWith, SerializeWith, compose_deserialize, compose_serialize};
+use serde::{Deserializer, Serializer};
+use std::marker::PhantomData;
+
+pub struct BoxF<W>(PhantomData<W>);
+
+impl<W> SerializeWith for BoxF<W>
+where
+    W: SerializeWith,
+{
+    type T = Box<W::T>;
+
+    fn serialize<S>(value: &Self::T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        compose_serialize(
+            |value, serializer, serialize| serialize(Box::as_ref(value), serializer),
+            W::serialize,
+        )(value, serializer)
+    }
+}
+
+impl<W> DeserializeWith for BoxF<W>
+where
+    W: DeserializeWith,
+{
+    type T = Box<W::T>;
+
+    fn deserialize<'de, D>(deserializer: D) -> Result<Self::T, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        compose_deserialize(W::deserialize, |value| Ok(Box::new(value)))(deserializer)
+    }
+}
@@ -0,0 +1,37 @@
+use super::{DeserializeWith, SerializeWith, compose_deserialize, compose_serialize};
+use serde::{Deserializer, Serializer};
+use std::{cell::Cell, marker::PhantomData};
+
+pub struct CellF<W>(PhantomData<W>);
+
+impl<W> SerializeWith for CellF<W>
+where
+    W: SerializeWith,
+    W::T: Copy,
+{
+    type T = Cell<W::T>;
+
+    fn serialize<S>(value: &Self::T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        compose_serialize(
+            |value, serializer, serialize| serialize(&Cell::get(value), serializer),
+            W::serialize,
+        )(value, serializer)
+    }
+}
+
+impl<W> DeserializeWith for CellF<W>
+where
+    W: DeserializeWith,
+{
+    type T = Cell<W::T>;
+
+    fn deserialize<'de, D>(deserializer: D) -> Result<Self::T, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        compose_deserialize(W::deserialize, |value| Ok(Cell::new(value)))(deserializer)
+    }
+}
@@ -1,4 +1,4 @@
-use serde::{Deserializer, Serializer};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 /// Trait representing a serializing Serde combinator.
 ///
@@ -90,14 +90,59 @@ where
     }
 }
 
+/// Adapts a [`SerializeWith`] to [`Serialize`], for use where a combinator must hand a value to a
+/// serializer it does not control (e.g., the one passed by [`Serializer::serialize_some`]).
+struct SerializeAs<'a, W: SerializeWith>(&'a W::T);
+
+impl<W> Serialize for SerializeAs<'_, W>
+where
+    W: SerializeWith,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        W::serialize(self.0, serializer)
+    }
+}
+
+/// Adapts a [`DeserializeWith`] to [`Deserialize`]. The counterpart of [`SerializeAs`].
+struct DeserializeAs<W: DeserializeWith>(W::T);
+
+impl<'de, W> Deserialize<'de> for DeserializeAs<W>
+where
+    W: DeserializeWith,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        W::deserialize(deserializer).map(Self)
+    }
+}
+
+mod arc;
+mod box_;
+mod cell;
 mod mutex;
+mod once_lock;
+mod rc;
 mod ref_;
+mod ref_cell;
 mod ref_mut;
 mod rw_lock;
+mod shared;
 mod type_;
 
+pub use arc::ArcF;
+pub use box_::BoxF;
+pub use cell::CellF;
 pub use mutex::MutexF;
+pub use once_lock::OnceLockF;
+pub use rc::RcF;
 pub use ref_::RefF;
+pub use ref_cell::RefCellF;
 pub use ref_mut::RefMutF;
 pub use rw_lock::RwLockF;
+pub use shared::{SharedArcF, SharedRcF, shared_scope};
 pub use type_::Type;
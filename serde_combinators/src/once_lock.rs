@@ -0,0 +1,40 @@
+use super::{DeserializeAs, DeserializeWith, SerializeAs, SerializeWith, compose_deserialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{marker::PhantomData, sync::OnceLock};
+
+/// Serializes an uninitialized [`OnceLock`] as `None` and an initialized one as `Some`.
+pub struct OnceLockF<W>(PhantomData<W>);
+
+impl<W> SerializeWith for OnceLockF<W>
+where
+    W: SerializeWith,
+{
+    type T = OnceLock<W::T>;
+
+    fn serialize<S>(value: &Self::T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        // smoelius: `compose_serialize` cannot be used here. The value inside the `Option` is
+        // serialized with whatever serializer `serialize_some` provides, which need not be `S`.
+        OnceLock::get(value)
+            .map(SerializeAs::<W>)
+            .serialize(serializer)
+    }
+}
+
+impl<W> DeserializeWith for OnceLockF<W>
+where
+    W: DeserializeWith,
+{
+    type T = OnceLock<W::T>;
+
+    fn deserialize<'de, D>(deserializer: D) -> Result<Self::T, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        compose_deserialize(Option::<DeserializeAs<W>>::deserialize, |value| {
+            Ok(value.map_or_else(OnceLock::new, |DeserializeAs(value)| OnceLock::from(value)))
+        })(deserializer)
+    }
+}
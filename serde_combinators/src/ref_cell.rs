@@ -0,0 +1,39 @@
+use super::{DeserializeWith, SerializeWith, compose_deserialize, compose_serialize};
+use serde::{Deserializer, Serializer};
+use std::{cell::RefCell, marker::PhantomData};
+
+pub struct RefCellF<W>(PhantomData<W>);
+
+impl<W> SerializeWith for RefCellF<W>
+where
+    W: SerializeWith,
+{
+    type T = RefCell<W::T>;
+
+    fn serialize<S>(value: &Self::T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        compose_serialize(
+            |value, serializer, serialize| {
+                let value = RefCell::try_borrow(value).map_err(serde::ser::Error::custom)?;
+                serialize(&value, serializer)
+            },
+            W::serialize,
+        )(value, serializer)
+    }
+}
+
+impl<W> DeserializeWith for RefCellF<W>
+where
+    W: DeserializeWith,
+{
+    type T = RefCell<W::T>;
+
+    fn deserialize<'de, D>(deserializer: D) -> Result<Self::T, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        compose_deserialize(W::deserialize, |value| Ok(RefCell::new(value)))(deserializer)
+    }
+}
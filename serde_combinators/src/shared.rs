@@ -0,0 +1,160 @@
+use super::{DeserializeAs, DeserializeWith, SerializeAs, SerializeWith};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{any::Any, cell::RefCell, collections::HashMap, marker::PhantomData, rc::Rc, sync::Arc};
+
+/// Like [`RcF`], but preserves sharing among the [`Rc`]s serialized or deserialized within a
+/// [`shared_scope`].
+///
+/// An [`Rc`] is serialized as an id paired with its value. When the same [`Rc`] is serialized
+/// again within the scope, only the id is written. When deserializing, the id is used to hand out
+/// a clone of the [`Rc`] deserialized first. Outside of a scope, every [`Rc`] is serialized and
+/// deserialized independently.
+///
+/// [`RcF`]: crate::RcF
+pub struct SharedRcF<W>(PhantomData<W>);
+
+/// Like [`SharedRcF`], but for [`Arc`].
+pub struct SharedArcF<W>(PhantomData<W>);
+
+#[derive(Default)]
+struct Scope {
+    ids: HashMap<*const (), u32>,
+    values: HashMap<u32, Box<dyn Any>>,
+}
+
+thread_local! {
+    static SCOPE: RefCell<Option<Scope>> = const { RefCell::new(None) };
+}
+
+struct ScopeGuard;
+
+impl Drop for ScopeGuard {
+    fn drop(&mut self) {
+        SCOPE.with_borrow_mut(|scope| *scope = None);
+    }
+}
+
+/// Calls `f` within a scope in which [`SharedRcF`] and [`SharedArcF`] preserve sharing. Nested
+/// calls share their outermost scope.
+pub fn shared_scope<T>(f: impl FnOnce() -> T) -> T {
+    let outermost = SCOPE.with_borrow_mut(|scope| {
+        if scope.is_some() {
+            return false;
+        }
+        *scope = Some(Scope::default());
+        true
+    });
+    let _guard = outermost.then_some(ScopeGuard);
+    f()
+}
+
+/// Returns the id to write for `ptr`, and whether the pointed-to value must be written too.
+fn serialize_id(ptr: *const ()) -> (u32, bool) {
+    SCOPE.with_borrow_mut(|scope| {
+        let Some(scope) = scope else {
+            return (0, true);
+        };
+        if let Some(&id) = scope.ids.get(&ptr) {
+            return (id, false);
+        }
+        let id = u32::try_from(scope.ids.len()).unwrap_or(u32::MAX);
+        scope.ids.insert(ptr, id);
+        (id, true)
+    })
+}
+
+fn serialize_shared<W, S>(ptr: *const (), value: &W::T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    W: SerializeWith,
+    S: Serializer,
+{
+    let (id, first) = serialize_id(ptr);
+    (id, first.then_some(SerializeAs::<W>(value))).serialize(serializer)
+}
+
+fn deserialize_shared<'de, W, P, D>(
+    deserializer: D,
+    new: impl FnOnce(W::T) -> P,
+) -> Result<P, D::Error>
+where
+    W: DeserializeWith,
+    P: Clone + 'static,
+    D: Deserializer<'de>,
+{
+    let (id, value) = <(u32, Option<DeserializeAs<W>>)>::deserialize(deserializer)?;
+    SCOPE.with_borrow_mut(|scope| match (scope, value) {
+        (None, Some(DeserializeAs(value))) => Ok(new(value)),
+        (Some(scope), Some(DeserializeAs(value))) => {
+            let ptr = new(value);
+            scope.values.insert(id, Box::new(ptr.clone()));
+            Ok(ptr)
+        }
+        (Some(scope), None) => scope
+            .values
+            .get(&id)
+            .and_then(|ptr| ptr.downcast_ref::<P>())
+            .cloned()
+            .ok_or_else(|| serde::de::Error::custom(format!("unknown shared value id: {id}"))),
+        (None, None) => Err(serde::de::Error::custom(
+            "shared value id encountered outside of scope",
+        )),
+    })
+}
+
+impl<W> SerializeWith for SharedRcF<W>
+where
+    W: SerializeWith,
+{
+    type T = Rc<W::T>;
+
+    fn serialize<S>(value: &Self::T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serialize_shared::<W, S>(Rc::as_ptr(value).cast(), value, serializer)
+    }
+}
+
+impl<W> DeserializeWith for SharedRcF<W>
+where
+    W: DeserializeWith,
+    W::T: 'static,
+{
+    type T = Rc<W::T>;
+
+    fn deserialize<'de, D>(deserializer: D) -> Result<Self::T, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_shared::<W, _, _>(deserializer, Rc::new)
+    }
+}
+
+impl<W> SerializeWith for SharedArcF<W>
+where
+    W: SerializeWith,
+{
+    type T = Arc<W::T>;
+
+    fn serialize<S>(value: &Self::T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serialize_shared::<W, S>(Arc::as_ptr(value).cast(), value, serializer)
+    }
+}
+
+impl<W> DeserializeWith for SharedArcF<W>
+where
+    W: DeserializeWith,
+    W::T: 'static,
+{
+    type T = Arc<W::T>;
+
+    fn deserialize<'de, D>(deserializer: D) -> Result<Self::T, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_shared::<W, _, _>(deserializer, Arc::new)
+    }
+}
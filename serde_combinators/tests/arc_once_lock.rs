@@ -0,0 +1,67 @@
+use serde::{Deserialize, Serialize};
+use serde_assert::{Deserializer, Serializer, Token};
+use serde_combinators::{ArcF, OnceLockF, Type, With};
+use std::sync::{Arc, OnceLock};
+
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
+struct Struct {
+    #[serde(with = "ArcF::<OnceLockF<Type<_>>>")]
+    arc_once_lock: Arc<OnceLock<i32>>,
+}
+
+#[cfg_attr(
+    dylint_lib = "assert_eq_arg_misordering",
+    allow(assert_eq_arg_misordering)
+)]
+#[test]
+fn serde_initialized() {
+    let strukt = Struct {
+        arc_once_lock: Arc::new(OnceLock::from(0)),
+    };
+    let serializer = Serializer::builder().build();
+    let tokens = strukt.serialize(&serializer).unwrap();
+    assert_eq!(
+        tokens,
+        [
+            Token::Struct {
+                name: "Struct",
+                len: 1
+            },
+            Token::Field("arc_once_lock"),
+            Token::Some,
+            Token::I32(0),
+            Token::StructEnd
+        ]
+    );
+    let mut deserializer = Deserializer::builder(tokens).build();
+    let other = Struct::deserialize(&mut deserializer).unwrap();
+    assert_eq!(strukt, other);
+}
+
+#[cfg_attr(
+    dylint_lib = "assert_eq_arg_misordering",
+    allow(assert_eq_arg_misordering)
+)]
+#[test]
+fn serde_uninitialized() {
+    let strukt = Struct {
+        arc_once_lock: Arc::new(OnceLock::new()),
+    };
+    let serializer = Serializer::builder().build();
+    let tokens = strukt.serialize(&serializer).unwrap();
+    assert_eq!(
+        tokens,
+        [
+            Token::Struct {
+                name: "Struct",
+                len: 1
+            },
+            Token::Field("arc_once_lock"),
+            Token::None,
+            Token::StructEnd
+        ]
+    );
+    let mut deserializer = Deserializer::builder(tokens).build();
+    let other = Struct::deserialize(&mut deserializer).unwrap();
+    assert_eq!(strukt, other);
+}
@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+use serde_assert::{Deserializer, Serializer, Token};
+use serde_combinators::{BoxF, CellF, Type, With};
+use std::cell::Cell;
+
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
+struct Struct {
+    #[serde(with = "BoxF::<CellF<Type<_>>>")]
+    box_cell: Box<Cell<i32>>,
+}
+
+#[test]
+fn set() {
+    let strukt = Struct {
+        box_cell: Box::new(Cell::new(0)),
+    };
+    strukt.box_cell.set(1);
+    let serializer = Serializer::builder().build();
+    let tokens = strukt.serialize(&serializer).unwrap();
+    let mut deserializer = Deserializer::builder(tokens).build();
+    let other = Struct::deserialize(&mut deserializer).unwrap();
+    assert_eq!(1, other.box_cell.get());
+}
+
+#[cfg_attr(
+    dylint_lib = "assert_eq_arg_misordering",
+    allow(assert_eq_arg_misordering)
+)]
+#[test]
+fn serde() {
+    let strukt = Struct {
+        box_cell: Box::new(Cell::new(0)),
+    };
+    let serializer = Serializer::builder().build();
+    let tokens = strukt.serialize(&serializer).unwrap();
+    assert_eq!(
+        tokens,
+        [
+            Token::Struct {
+                name: "Struct",
+                len: 1
+            },
+            Token::Field("box_cell"),
+            Token::I32(0),
+            Token::StructEnd
+        ]
+    );
+    let mut deserializer = Deserializer::builder(tokens).build();
+    let other = Struct::deserialize(&mut deserializer).unwrap();
+    assert_eq!(strukt, other);
+}
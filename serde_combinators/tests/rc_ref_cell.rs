@@ -0,0 +1,48 @@
+use serde::{Deserialize, Serialize};
+use serde_assert::{Deserializer, Serializer, Token};
+use serde_combinators::{RcF, RefCellF, Type, With};
+use std::{cell::RefCell, rc::Rc};
+
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
+struct Struct {
+    #[serde(with = "RcF::<RefCellF<Type<_>>>")]
+    rc_ref_cell: Rc<RefCell<i32>>,
+}
+
+#[test]
+fn borrowed_mutably() {
+    let strukt = Struct {
+        rc_ref_cell: Rc::new(RefCell::new(0)),
+    };
+    let _ref_mut = strukt.rc_ref_cell.borrow_mut();
+    let serializer = Serializer::builder().build();
+    assert!(strukt.serialize(&serializer).is_err());
+}
+
+#[cfg_attr(
+    dylint_lib = "assert_eq_arg_misordering",
+    allow(assert_eq_arg_misordering)
+)]
+#[test]
+fn serde() {
+    let strukt = Struct {
+        rc_ref_cell: Rc::new(RefCell::new(0)),
+    };
+    let serializer = Serializer::builder().build();
+    let tokens = strukt.serialize(&serializer).unwrap();
+    assert_eq!(
+        tokens,
+        [
+            Token::Struct {
+                name: "Struct",
+                len: 1
+            },
+            Token::Field("rc_ref_cell"),
+            Token::I32(0),
+            Token::StructEnd
+        ]
+    );
+    let mut deserializer = Deserializer::builder(tokens).build();
+    let other = Struct::deserialize(&mut deserializer).unwrap();
+    assert_eq!(strukt, other);
+}
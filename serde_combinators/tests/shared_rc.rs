@@ -0,0 +1,88 @@
+use serde::{Deserialize, Serialize};
+use serde_assert::{Deserializer, Serializer, Token};
+use serde_combinators::{RefCellF, SharedRcF, Type, With, shared_scope};
+use std::{cell::RefCell, rc::Rc};
+
+#[derive(Deserialize, Serialize, Debug)]
+struct Struct {
+    #[serde(with = "SharedRcF::<RefCellF<Type<_>>>")]
+    x: Rc<RefCell<i32>>,
+    #[serde(with = "SharedRcF::<RefCellF<Type<_>>>")]
+    y: Rc<RefCell<i32>>,
+}
+
+#[cfg_attr(
+    dylint_lib = "assert_eq_arg_misordering",
+    allow(assert_eq_arg_misordering)
+)]
+#[test]
+fn serde_shared() {
+    let rc = Rc::new(RefCell::new(0));
+    let strukt = Struct {
+        x: rc.clone(),
+        y: rc,
+    };
+    let serializer = Serializer::builder().build();
+    let tokens = shared_scope(|| strukt.serialize(&serializer)).unwrap();
+    assert_eq!(
+        tokens,
+        [
+            Token::Struct {
+                name: "Struct",
+                len: 2
+            },
+            Token::Field("x"),
+            Token::Tuple { len: 2 },
+            Token::U32(0),
+            Token::Some,
+            Token::I32(0),
+            Token::TupleEnd,
+            Token::Field("y"),
+            Token::Tuple { len: 2 },
+            Token::U32(0),
+            Token::None,
+            Token::TupleEnd,
+            Token::StructEnd
+        ]
+    );
+    let mut deserializer = Deserializer::builder(tokens).build();
+    let other = shared_scope(|| Struct::deserialize(&mut deserializer)).unwrap();
+    assert!(Rc::ptr_eq(&other.x, &other.y));
+}
+
+#[test]
+fn serde_unshared() {
+    let strukt = Struct {
+        x: Rc::new(RefCell::new(0)),
+        y: Rc::new(RefCell::new(0)),
+    };
+    let serializer = Serializer::builder().build();
+    let tokens = shared_scope(|| strukt.serialize(&serializer)).unwrap();
+    let mut deserializer = Deserializer::builder(tokens).build();
+    let other = shared_scope(|| Struct::deserialize(&mut deserializer)).unwrap();
+    assert!(!Rc::ptr_eq(&other.x, &other.y));
+}
+
+#[test]
+fn deserialize_unknown_id() {
+    let tokens = [
+        Token::Struct {
+            name: "Struct",
+            len: 2,
+        },
+        Token::Field("x"),
+        Token::Tuple { len: 2 },
+        Token::U32(0),
+        Token::Some,
+        Token::I32(0),
+        Token::TupleEnd,
+        Token::Field("y"),
+        Token::Tuple { len: 2 },
+        Token::U32(1),
+        Token::None,
+        Token::TupleEnd,
+        Token::StructEnd,
+    ];
+    let mut deserializer = Deserializer::builder(tokens).build();
+    assert!(shared_scope(|| Struct::deserialize(&mut deserializer)).is_err());
+}
@@ -0,0 +1,121 @@
+use serde::{Serialize, de::DeserializeOwned};
+use std::{
+    cell::{Cell, RefCell},
+    sync::{
+        LazyLock, Mutex, PoisonError, RwLock,
+        atomic::{
+            AtomicBool, AtomicI8, AtomicI16, AtomicI32, AtomicI64, AtomicIsize, AtomicU8,
+            AtomicU16, AtomicU32, AtomicU64, AtomicUsize, Ordering,
+        },
+    },
+    thread::LocalKey,
+};
+
+/// Trait whose implementation is required by the [`test_fuzz` macro]'s [`capture_globals`]
+/// option.
+///
+/// A `Global` is a `static` (or `thread_local!`) whose value can be captured when a corpus file
+/// is written, and restored when the corpus file is read.
+///
+/// [`capture_globals`]: https://github.com/trailofbits/test-fuzz/blob/master/README.md#capture_globals--globals
+/// [`test_fuzz` macro]: https://github.com/trailofbits/test-fuzz/blob/master/README.md#test_fuzz-macro
+pub trait Global {
+    type Value: Serialize + DeserializeOwned;
+    fn capture(&'static self) -> Self::Value;
+    fn restore(&'static self, value: Self::Value);
+}
+
+impl<T> Global for Mutex<T>
+where
+    T: Clone + Serialize + DeserializeOwned,
+{
+    type Value = T;
+    fn capture(&'static self) -> T {
+        self.lock().unwrap_or_else(PoisonError::into_inner).clone()
+    }
+    fn restore(&'static self, value: T) {
+        *self.lock().unwrap_or_else(PoisonError::into_inner) = value;
+    }
+}
+
+impl<T> Global for RwLock<T>
+where
+    T: Clone + Serialize + DeserializeOwned,
+{
+    type Value = T;
+    fn capture(&'static self) -> T {
+        self.read().unwrap_or_else(PoisonError::into_inner).clone()
+    }
+    fn restore(&'static self, value: T) {
+        *self.write().unwrap_or_else(PoisonError::into_inner) = value;
+    }
+}
+
+impl<T> Global for LazyLock<T>
+where
+    T: Global,
+{
+    type Value = T::Value;
+    fn capture(&'static self) -> T::Value {
+        Self::force(self).capture()
+    }
+    fn restore(&'static self, value: T::Value) {
+        Self::force(self).restore(value);
+    }
+}
+
+impl<T> Global for LocalKey<RefCell<T>>
+where
+    T: Clone + Serialize + DeserializeOwned,
+{
+    type Value = T;
+    fn capture(&'static self) -> T {
+        self.with_borrow(Clone::clone)
+    }
+    fn restore(&'static self, value: T) {
+        self.set(value);
+    }
+}
+
+impl<T> Global for LocalKey<Cell<T>>
+where
+    T: Copy + Serialize + DeserializeOwned,
+{
+    type Value = T;
+    fn capture(&'static self) -> T {
+        self.get()
+    }
+    fn restore(&'static self, value: T) {
+        self.set(value);
+    }
+}
+
+macro_rules! impl_global_for_atomic {
+    ($($atomic:ty: $ty:ty),* $(,)?) => {
+        $(
+            impl Global for $atomic {
+                type Value = $ty;
+                fn capture(&'static self) -> $ty {
+                    self.load(Ordering::SeqCst)
+                }
+                fn restore(&'static self, value: $ty) {
+                    self.store(value, Ordering::SeqCst);
+                }
+            }
+        )*
+    };
+}
+
+impl_global_for_atomic!(
+    AtomicBool: bool,
+    AtomicI8: i8,
+    AtomicI16: i16,
+    AtomicI32: i32,
+    AtomicI64: i64,
+    AtomicIsize: isize,
+    AtomicU8: u8,
+    AtomicU16: u16,
+    AtomicU32: u32,
+    AtomicU64: u64,
+    AtomicUsize: usize,
+);
@@ -1,5 +1,19 @@
 pub use runtime;
-pub use test_fuzz_macro::{test_fuzz, test_fuzz_impl};
+pub use runtime::{SequenceId, capturing};
+pub use test_fuzz_macro::{register_fn, test_fuzz, test_fuzz_impl};
+
+/// Enables capturing in the binary in which it appears.
+///
+/// Calls to fuzz targets made from such a binary (e.g., an integration test or a `src/bin` target)
+/// are written to corpus files, just as they are when running a package's own tests.
+#[macro_export]
+macro_rules! capture_hook {
+    () => {
+        $crate::runtime::inventory::submit! {
+            $crate::runtime::CaptureHook
+        }
+    };
+}
 
 // smoelius: Re-export afl so that test-fuzz clients do not need to add it to their Cargo.toml
 // files.
@@ -15,6 +29,9 @@ pub use cast_checks;
 
 pub use internal::serde_format;
 
+// smoelius: Re-export `serde_combinators` so that the `test_fuzz` macro can refer to it.
+pub use serde_combinators;
+
 mod utils;
 pub use utils::{
     deserialize_ref, deserialize_ref_mut, serde_ref, serde_ref_mut, serialize_ref,
@@ -23,3 +40,6 @@ pub use utils::{
 
 mod convert;
 pub use convert::{FromRef, Into};
+
+mod global;
+pub use global::Global;
@@ -51,6 +51,44 @@ macro_rules! leak {
     };
 }
 
+/// Define a fuzz target `$name` that takes a value of type `$ty`, encodes it with `$encode`,
+/// decodes the result with `$decode`, and asserts that the decoded value equals the original.
+///
+/// If `$decode` is preceded by `try`, it should return a `Result`, and the target panics if it
+/// returns an error.
+#[macro_export]
+macro_rules! roundtrip {
+    (@define $name:ident, $ty:ty, $encode:expr, $decode:expr, $unwrap:expr) => {
+        #[$crate::test_fuzz]
+        fn $name(value: $ty) {
+            let encoded = ($encode)(&value);
+            let decoded: $ty = ($unwrap)(($decode)(&encoded));
+            assert_eq!(
+                value,
+                decoded,
+                "`{}` is not the inverse of `{}`",
+                stringify!($decode),
+                stringify!($encode)
+            );
+        }
+    };
+    ($name:ident, $ty:ty, $encode:expr, try $decode:expr) => {
+        $crate::roundtrip!(@define $name, $ty, $encode, $decode, |decoded: Result<$ty, _>| {
+            decoded.unwrap_or_else(|error| {
+                panic!(
+                    "`{}` could not decode the output of `{}`: {:?}",
+                    stringify!($decode),
+                    stringify!($encode),
+                    error
+                )
+            })
+        });
+    };
+    ($name:ident, $ty:ty, $encode:expr, $decode:expr) => {
+        $crate::roundtrip!(@define $name, $ty, $encode, $decode, |decoded: $ty| decoded);
+    };
+}
+
 pub mod serde_ref {
     pub use super::deserialize_ref as deserialize;
     pub use super::serialize_ref as serialize;
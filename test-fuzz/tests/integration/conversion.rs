@@ -12,7 +12,9 @@ fn conversion() {
         .args(["--features", "__inapplicable_conversion"])
         .logged_assert()
         .failure()
-        .stderr(predicate::str::is_match(r#"(?m)\bConversion "Y" -> "Z" does not apply to the following candidates: \{\s*"X",\s*}$"#).unwrap());
+        .stderr(predicate::str::is_match(r#"(?m)\bConversion "Y" -> "Z" does not apply to the following candidates: \{\s*"X",\s*}$"#).unwrap())
+        .stderr(predicate::str::contains(r#"#[test_fuzz::test_fuzz(convert = "Y, Z")]"#))
+        .stderr(predicate::str::contains("proc macro panicked").not());
 }
 
 fn test() -> Command {